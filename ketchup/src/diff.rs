@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use tracing::info;
+
+/// Volatile fields that must be ignored when comparing two captures of the same
+/// object, so a diff reflects intentional change rather than cluster churn.
+const VOLATILE_METADATA: &[&str] = &["resourceVersion", "managedFields", "uid", "generation"];
+
+/// The result of comparing a freshly-collected bundle against a prior one.
+#[derive(Debug, Default)]
+pub struct DiffReport {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+}
+
+/// Load a ketchup bundle into a map of `apiVersion/kind/namespace/name` ->
+/// normalized manifest. Accepts either an output directory or the compressed
+/// archive produced by `handle_compression`.
+pub fn load_bundle(path: &str) -> Result<BTreeMap<String, Value>> {
+    let p = Path::new(path);
+    if p.is_dir() {
+        load_from_dir(p)
+    } else {
+        load_from_archive(p)
+    }
+}
+
+fn load_from_dir(dir: &Path) -> Result<BTreeMap<String, Value>> {
+    let mut objects = BTreeMap::new();
+    visit_dir(dir, &mut |name, bytes| {
+        if let Some((key, value)) = parse_object(name, bytes) {
+            objects.insert(key, value);
+        }
+    })?;
+    Ok(objects)
+}
+
+fn visit_dir(dir: &Path, f: &mut impl FnMut(&str, &[u8])) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            visit_dir(&path, f)?;
+        } else if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            let bytes = std::fs::read(&path)?;
+            f(name, &bytes);
+        }
+    }
+    Ok(())
+}
+
+fn load_from_archive(archive: &Path) -> Result<BTreeMap<String, Value>> {
+    let file = std::fs::File::open(archive)
+        .with_context(|| format!("Failed to open archive {:?}", archive))?;
+    let dec = flate2::read::GzDecoder::new(file);
+    let mut tar = tar::Archive::new(dec);
+
+    let mut objects = BTreeMap::new();
+    for entry in tar.entries().context("Failed to read archive entries")? {
+        let mut entry = entry?;
+        let name = entry
+            .path()?
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|s| s.to_string());
+        let Some(name) = name else { continue };
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        if let Some((key, value)) = parse_object(&name, &bytes) {
+            objects.insert(key, value);
+        }
+    }
+    Ok(objects)
+}
+
+/// Parse a single manifest file into `(key, normalized value)`, skipping files
+/// that are not individual resource manifests (e.g. the summary/diff output).
+fn parse_object(name: &str, bytes: &[u8]) -> Option<(String, Value)> {
+    if name == "collection-summary.yaml" || name == "diff.yaml" {
+        return None;
+    }
+
+    let mut value: Value = if name.ends_with(".json") {
+        serde_json::from_slice(bytes).ok()?
+    } else if name.ends_with(".yaml") || name.ends_with(".yml") {
+        serde_yaml::from_slice(bytes).ok()?
+    } else {
+        return None;
+    };
+
+    let key = object_key(&value)?;
+    normalize(&mut value);
+    Some((key, value))
+}
+
+/// Build the `apiVersion/kind/namespace/name` identity key for an object.
+fn object_key(value: &Value) -> Option<String> {
+    let api_version = value.get("apiVersion")?.as_str()?;
+    let kind = value.get("kind")?.as_str()?;
+    let metadata = value.get("metadata")?;
+    let name = metadata.get("name")?.as_str()?;
+    let namespace = metadata
+        .get("namespace")
+        .and_then(|n| n.as_str())
+        .unwrap_or("");
+    Some(format!("{}/{}/{}/{}", api_version, kind, namespace, name))
+}
+
+/// Drop volatile fields so the comparison is structural.
+fn normalize(value: &mut Value) {
+    if let Some(obj) = value.as_object_mut() {
+        if let Some(metadata) = obj.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+            for field in VOLATILE_METADATA {
+                metadata.remove(*field);
+            }
+        }
+        obj.remove("status");
+    }
+}
+
+/// Compare the current bundle against the previous one.
+pub fn compute(current: &BTreeMap<String, Value>, previous: &BTreeMap<String, Value>) -> DiffReport {
+    let mut report = DiffReport::default();
+
+    for (key, value) in current {
+        match previous.get(key) {
+            None => report.added.push(key.clone()),
+            Some(prev) if prev != value => report.modified.push(key.clone()),
+            Some(_) => {}
+        }
+    }
+    for key in previous.keys() {
+        if !current.contains_key(key) {
+            report.removed.push(key.clone());
+        }
+    }
+
+    report.added.sort();
+    report.removed.sort();
+    report.modified.sort();
+    report
+}
+
+/// Emit the diff as a human-readable summary and a machine-readable `diff.yaml`.
+pub fn write_report(output_dir: &str, report: &DiffReport) -> Result<()> {
+    info!(
+        "🔍 Diff vs previous bundle: {} added, {} removed, {} modified",
+        report.added.len(),
+        report.removed.len(),
+        report.modified.len()
+    );
+
+    let doc = serde_json::json!({
+        "added": report.added,
+        "removed": report.removed,
+        "modified": report.modified,
+    });
+
+    let filename = format!("{}/diff.yaml", output_dir);
+    let contents = serde_yaml::to_string(&doc).context("Failed to serialize diff")?;
+    std::fs::write(&filename, contents).context("Failed to write diff.yaml")?;
+    info!("📄 Diff written to: {}", filename);
+
+    Ok(())
+}
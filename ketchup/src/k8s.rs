@@ -2,17 +2,51 @@ use anyhow::{Context, Result};
 use k8s_openapi::api::core::v1::Namespace;
 use kube::{
     api::{Api, DynamicObject, ListParams},
+    config::{KubeConfigOptions, Kubeconfig},
     discovery::{self, Scope},
     Client, Config, ResourceExt,
 };
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::path::Path;
 use tracing::{debug, info, warn};
 
+/// Path to the service-account token mounted into every Pod by the kubelet.
+const IN_CLUSTER_TOKEN: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
 pub struct KubeClient {
     client: Client,
 }
 
+/// Where the client should load its connection settings from.
+#[derive(Debug, Clone)]
+pub enum ConfigSource {
+    /// Load one or more kubeconfig files (merged the way kubectl does) and,
+    /// optionally, select a named context instead of `current-context`.
+    Kubeconfig {
+        paths: Vec<String>,
+        context: Option<String>,
+    },
+    /// Use the in-cluster service-account token and the `KUBERNETES_SERVICE_*`
+    /// environment, as when running as a Pod in the target cluster.
+    InCluster,
+    /// Detect the in-cluster environment first and fall back to kubeconfig.
+    Auto,
+}
+
+impl ConfigSource {
+    /// True when the mounted service-account token is present, i.e. we are
+    /// almost certainly running inside a Pod.
+    fn in_cluster_present() -> bool {
+        Path::new(IN_CLUSTER_TOKEN).exists()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CollectionOptions {
     pub include_secrets: bool,
@@ -23,17 +57,172 @@ pub struct CollectionOptions {
     pub include_leases: bool,
     pub specific_crds: Option<Vec<String>>,
     pub sanitize: bool,
+    pub namespace_allow: Vec<Pattern>,
+    pub namespace_deny: Vec<Pattern>,
+    pub redaction: RedactionPolicy,
+    pub max_concurrency: usize,
+    pub collect_all_crd_versions: bool,
+    pub mode: CollectionMode,
 }
 
-impl KubeClient {
-    /// Create a new Kubernetes client using the specified kubeconfig file
-    pub async fn new_client(kubeconfig_path: &str) -> Result<Self> {
-        info!("Loading kubeconfig from: {}", kubeconfig_path);
+/// How the set of collected kinds is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionMode {
+    /// Curated set driven by the per-kind `include_*` flags (the default).
+    Curated,
+    /// Every listable resource the API server exposes, including operator CRDs,
+    /// with no per-kind flags required.
+    Discover,
+}
 
-        // Set the KUBECONFIG environment variable
-        std::env::set_var("KUBECONFIG", kubeconfig_path);
+/// How aggressively to scrub payloads out of collected objects before they
+/// leave the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactionPolicy {
+    /// Leave payloads untouched.
+    None,
+    /// Redact `Secret` `data`/`stringData` values only.
+    SecretsOnly,
+    /// Redact Secrets and, additionally, ConfigMap keys matching the denylist.
+    Aggressive,
+}
 
-        let config = Config::infer().await.context("Failed to load kubeconfig")?;
+/// A namespace matcher: either a shell-style glob (`kube-*`) or, when prefixed
+/// with `re:`, an anchored regular expression (`re:^kube-.*$`).
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    /// Parse a pattern string. A `re:` prefix selects regex mode; anything else
+    /// is treated as a glob.
+    pub fn parse(spec: &str) -> Result<Self> {
+        if let Some(rest) = spec.strip_prefix("re:") {
+            // Anchor so the pattern must match the whole namespace name.
+            let anchored = format!("^(?:{})$", rest);
+            let re = Regex::new(&anchored)
+                .with_context(|| format!("Invalid regex pattern: {}", rest))?;
+            Ok(Pattern::Regex(re))
+        } else {
+            Ok(Pattern::Glob(spec.to_string()))
+        }
+    }
+
+    /// True when `name` matches this pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Pattern::Glob(glob) => glob_matches(glob, name),
+            Pattern::Regex(re) => re.is_match(name),
+        }
+    }
+}
+
+/// Match `name` against a glob supporting `*` (any run) and `?` (single char).
+fn glob_matches(glob: &str, name: &str) -> bool {
+    let pattern = glob.as_bytes();
+    let text = name.as_bytes();
+    // Classic two-pointer glob matcher with backtracking on `*`.
+    let (mut p, mut t) = (0usize, 0usize);
+    let (mut star, mut mark) = (None, 0usize);
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some(p);
+            mark = t;
+            p += 1;
+        } else if let Some(sp) = star {
+            p = sp + 1;
+            mark += 1;
+            t = mark;
+        } else {
+            return false;
+        }
+    }
+    while p < pattern.len() && pattern[p] == b'*' {
+        p += 1;
+    }
+    p == pattern.len()
+}
+
+/// A node in the reference graph, identified the way Kubernetes itself does:
+/// by API group/version, kind, namespace (empty for cluster-scoped) and name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceKey {
+    pub api_version: String,
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+impl ResourceKey {
+    fn from_value(value: &Value) -> Option<Self> {
+        let api_version = value.get("apiVersion")?.as_str()?.to_string();
+        let kind = value.get("kind")?.as_str()?.to_string();
+        let metadata = value.get("metadata")?;
+        let name = metadata.get("name")?.as_str()?.to_string();
+        let namespace = metadata
+            .get("namespace")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default()
+            .to_string();
+        Some(ResourceKey {
+            api_version,
+            kind,
+            namespace,
+            name,
+        })
+    }
+}
+
+/// A directed edge from a referring object to the object it points at.
+#[derive(Debug, Clone)]
+pub struct ReferenceEdge {
+    pub from: ResourceKey,
+    pub to: ResourceKey,
+    /// How the reference was discovered, e.g. `ownerReference` or
+    /// `spec.volumes[].configMap`.
+    pub via: String,
+}
+
+/// The dependency closure of a set of collected objects: every node we saw or
+/// pulled in, the edges between them, and any referenced object we could not
+/// resolve (a dangling reference worth surfacing to the user).
+#[derive(Debug, Default)]
+pub struct ReferenceGraph {
+    pub nodes: Vec<ResourceKey>,
+    pub edges: Vec<ReferenceEdge>,
+    pub missing: Vec<ResourceKey>,
+    /// Objects fetched because something referenced them but they were not in
+    /// the original collection.
+    pub resolved: Vec<Value>,
+}
+
+impl KubeClient {
+    /// Create a new Kubernetes client from the given config source.
+    ///
+    /// `ConfigSource::Auto` prefers the in-cluster service-account token so the
+    /// collector can be scheduled as a Job in the affected cluster, and only
+    /// falls back to the kubeconfig path when that token is absent.
+    pub async fn new_client(source: &ConfigSource) -> Result<Self> {
+        let config = match source {
+            ConfigSource::Kubeconfig { paths, context } => {
+                Self::kubeconfig_config(paths, context.as_deref()).await?
+            }
+            ConfigSource::InCluster => Self::in_cluster_config()?,
+            ConfigSource::Auto => {
+                if ConfigSource::in_cluster_present() {
+                    info!("Detected in-cluster environment, using service-account token");
+                    Self::in_cluster_config()?
+                } else {
+                    debug!("No service-account token found, falling back to kubeconfig");
+                    Config::infer().await.context("Failed to load kubeconfig")?
+                }
+            }
+        };
 
         let client = Client::try_from(config).context("Failed to create Kubernetes client")?;
 
@@ -41,6 +230,31 @@ impl KubeClient {
         Ok(KubeClient { client })
     }
 
+    /// Load config from one or more merged kubeconfig files, honoring an
+    /// explicit context selection.
+    async fn kubeconfig_config(paths: &[String], context: Option<&str>) -> Result<Config> {
+        let kubeconfig = load_merged_kubeconfig(paths)?;
+
+        let options = KubeConfigOptions {
+            context: context.map(|c| c.to_string()),
+            ..Default::default()
+        };
+        if let Some(ctx) = context {
+            info!("Using context: {}", ctx);
+        }
+
+        Config::from_custom_kubeconfig(kubeconfig, &options)
+            .await
+            .context("Failed to build config from kubeconfig")
+    }
+
+    /// Load config from the mounted service-account token and the
+    /// `KUBERNETES_SERVICE_HOST`/`KUBERNETES_SERVICE_PORT` environment.
+    fn in_cluster_config() -> Result<Config> {
+        info!("Loading in-cluster config from service-account token");
+        Config::incluster().context("Failed to load in-cluster config")
+    }
+
     /// List all available namespaces in the cluster
     pub async fn list_namespaces(&self) -> Result<Vec<String>> {
         debug!("Fetching list of namespaces...");
@@ -62,19 +276,53 @@ impl KubeClient {
         Ok(names)
     }
 
-    /// Verify that specified namespaces exist
-    pub async fn verify_namespaces(&self, requested: &[String]) -> Result<Vec<String>> {
+    /// Resolve the set of namespaces to collect from.
+    ///
+    /// When `opts.namespace_allow` is non-empty the allow patterns are expanded
+    /// against the live namespace list (warning on any that match nothing);
+    /// otherwise the explicitly `requested` names are matched exactly. In both
+    /// cases the deny patterns are subtracted afterwards, so deny always wins
+    /// over allow on a conflict.
+    pub async fn verify_namespaces(
+        &self,
+        requested: &[String],
+        opts: &CollectionOptions,
+    ) -> Result<Vec<String>> {
         let available = self.list_namespaces().await?;
         let mut verified = Vec::new();
 
-        for ns in requested {
-            if available.contains(ns) {
-                verified.push(ns.clone());
-            } else {
-                warn!("⚠️  Namespace '{}' does not exist, skipping", ns);
+        if opts.namespace_allow.is_empty() {
+            for ns in requested {
+                if available.contains(ns) {
+                    verified.push(ns.clone());
+                } else {
+                    warn!("⚠️  Namespace '{}' does not exist, skipping", ns);
+                }
+            }
+        } else {
+            for pattern in &opts.namespace_allow {
+                let matched: Vec<&String> =
+                    available.iter().filter(|ns| pattern.matches(ns)).collect();
+                if matched.is_empty() {
+                    warn!("⚠️  Allow pattern {:?} matched no namespaces", pattern);
+                }
+                for ns in matched {
+                    if !verified.contains(ns) {
+                        verified.push(ns.clone());
+                    }
+                }
             }
         }
 
+        // Deny wins: drop anything matching a deny pattern.
+        verified.retain(|ns| {
+            let denied = opts.namespace_deny.iter().any(|p| p.matches(ns));
+            if denied {
+                debug!("Namespace '{}' excluded by deny pattern", ns);
+            }
+            !denied
+        });
+
         if verified.is_empty() {
             anyhow::bail!("No valid namespaces found");
         }
@@ -82,90 +330,216 @@ impl KubeClient {
         Ok(verified)
     }
 
-    /// Collect cluster-scoped resources
-    pub async fn collect_cluster_resources(
+    /// Discover the collectable `ApiResource`s for a given scope, applying the
+    /// configured kind filters. Run once and shared across namespaces so we do
+    /// not rediscover the API surface for every namespace.
+    async fn discover_resources(
         &self,
+        scope: Scope,
         opts: &CollectionOptions,
-    ) -> Result<HashMap<String, Vec<Value>>> {
-        let mut resources: HashMap<String, Vec<Value>> = HashMap::new();
-
-        // Discover all API resources
+    ) -> Result<Vec<kube::discovery::ApiResource>> {
         let discovery = discovery::Discovery::new(self.client.clone()).run().await?;
 
-        // Filter for cluster-scoped resources
+        let mut resources = Vec::new();
         for group in discovery.groups() {
             for (ar, caps) in group.recommended_resources() {
-                // Skip if not cluster-scoped
-                if caps.scope != Scope::Cluster {
+                if caps.scope != scope {
                     continue;
                 }
 
-                // Apply filters
-                if !self.should_collect_resource(&ar.kind, opts) {
-                    debug!("Skipping cluster resource: {}", ar.kind);
+                // Only resources we can actually `list` are collectable.
+                if !caps.operations.iter().any(|op| op == "list") {
                     continue;
                 }
 
-                debug!("Collecting cluster resource: {}", ar.kind);
+                let keep = match opts.mode {
+                    // Discover mode captures the full surface, skipping only the
+                    // kinds that are never meaningfully collectable.
+                    CollectionMode::Discover => !is_always_skipped(&ar.kind),
+                    CollectionMode::Curated => self.should_collect_resource(&ar.kind, opts),
+                };
+                if !keep {
+                    debug!("Skipping {:?} resource: {}", scope, ar.kind);
+                    continue;
+                }
+                resources.push(ar);
+            }
+        }
+        Ok(resources)
+    }
 
-                match self.collect_dynamic_resource(&ar, None, opts).await {
-                    Ok(items) if !items.is_empty() => {
-                        info!("  ✅ {} ({})", ar.kind, items.len());
-                        resources.insert(ar.kind.clone(), items);
-                    }
-                    Ok(_) => {
-                        debug!("  ⏭️  {} (0 items)", ar.kind);
-                    }
-                    Err(e) => {
-                        warn!("  ⚠️  Failed to collect {}: {}", ar.kind, e);
-                    }
+    /// Collect cluster-scoped resources, fanning out one `list` per kind with a
+    /// bounded number of requests in flight.
+    pub async fn collect_cluster_resources(
+        &self,
+        opts: &CollectionOptions,
+    ) -> Result<HashMap<String, Vec<Value>>> {
+        let kinds = self.discover_resources(Scope::Cluster, opts).await?;
+
+        let results = stream::iter(kinds)
+            .map(|ar| async move {
+                let items = self.collect_dynamic_resource(&ar, None, opts).await;
+                (ar.kind.clone(), items)
+            })
+            .buffer_unordered(opts.max_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut resources: HashMap<String, Vec<Value>> = HashMap::new();
+        for (kind, items) in results {
+            match items {
+                Ok(items) if !items.is_empty() => {
+                    info!("  ✅ {} ({})", kind, items.len());
+                    resources.insert(kind, items);
                 }
+                Ok(_) => debug!("  ⏭️  {} (0 items)", kind),
+                // Per-kind errors degrade gracefully without aborting the run.
+                Err(e) => warn!("  ⚠️  Failed to collect {}: {}", kind, e),
             }
         }
 
         Ok(resources)
     }
 
-    /// Collect namespaced resources
+    /// Collect namespaced resources across every requested namespace.
+    ///
+    /// API discovery runs once and the resulting `ApiResource` set is shared;
+    /// one `list` task is spawned per `(namespace, kind)` pair and run through a
+    /// bounded concurrent executor so large clusters do not serialize.
     pub async fn collect_namespace_resources(
         &self,
-        namespace: &str,
+        namespaces: &[String],
+        opts: &CollectionOptions,
+    ) -> Result<HashMap<String, HashMap<String, Vec<Value>>>> {
+        let kinds = self.discover_resources(Scope::Namespaced, opts).await?;
+
+        // Build one task per (namespace, kind) pair.
+        let pairs: Vec<(String, kube::discovery::ApiResource)> = namespaces
+            .iter()
+            .flat_map(|ns| kinds.iter().cloned().map(move |ar| (ns.clone(), ar)))
+            .collect();
+
+        let results = stream::iter(pairs)
+            .map(|(ns, ar)| async move {
+                let items = self.collect_dynamic_resource(&ar, Some(&ns), opts).await;
+                (ns, ar.kind.clone(), items)
+            })
+            .buffer_unordered(opts.max_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut by_namespace: HashMap<String, HashMap<String, Vec<Value>>> = HashMap::new();
+        for (ns, kind, items) in results {
+            match items {
+                Ok(items) if !items.is_empty() => {
+                    debug!("  ✅ {}/{} ({})", ns, kind, items.len());
+                    by_namespace.entry(ns).or_default().insert(kind, items);
+                }
+                Ok(_) => {
+                    by_namespace.entry(ns).or_default();
+                }
+                // Per-kind errors degrade gracefully without aborting the run.
+                Err(e) => {
+                    debug!("  ⚠️  Failed to collect {}/{}: {}", ns, kind, e);
+                    by_namespace.entry(ns).or_default();
+                }
+            }
+        }
+
+        Ok(by_namespace)
+    }
+
+    /// Collect every served version of the selected CRDs.
+    ///
+    /// `recommended_resources()` only surfaces one served version, so bundles
+    /// would silently miss objects stored under other versions. For each
+    /// selected `CustomResourceDefinition` this enumerates `spec.versions[]`,
+    /// lists objects under each served version, and annotates every object with
+    /// the version it came from and whether that is the storage version, so
+    /// downstream tooling can reason about in-flight schema migrations.
+    pub async fn collect_crd_all_versions(
+        &self,
         opts: &CollectionOptions,
     ) -> Result<HashMap<String, Vec<Value>>> {
         let mut resources: HashMap<String, Vec<Value>> = HashMap::new();
 
-        // Discover all API resources
-        let discovery = discovery::Discovery::new(self.client.clone()).run().await?;
+        // List every CustomResourceDefinition in the cluster.
+        let crd_api: Api<DynamicObject> = Api::all_with(
+            self.client.clone(),
+            "apiextensions.k8s.io/v1",
+            "CustomResourceDefinition",
+        );
+        let crds = crd_api
+            .list(&ListParams::default())
+            .await
+            .context("Failed to list CustomResourceDefinitions")?;
 
-        // Filter for namespaced resources
-        for group in discovery.groups() {
-            for (ar, caps) in group.recommended_resources() {
-                // Skip if not namespaced
-                if caps.scope != Scope::Namespaced {
-                    continue;
-                }
+        for crd in crds.items {
+            let crd = serde_json::to_value(&crd)?;
+            let spec = match crd.get("spec") {
+                Some(s) => s,
+                None => continue,
+            };
 
-                // Apply filters
-                if !self.should_collect_resource(&ar.kind, opts) {
-                    debug!("Skipping namespaced resource: {}", ar.kind);
+            let group = spec.get("group").and_then(|g| g.as_str()).unwrap_or_default();
+            let names = spec.get("names");
+            let kind = names
+                .and_then(|n| n.get("kind"))
+                .and_then(|k| k.as_str())
+                .unwrap_or_default()
+                .to_string();
+            // Honor the same CRD selection as the rest of the collector.
+            let crd_name = crd
+                .get("metadata")
+                .and_then(|m| m.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or_default();
+            if !self.should_collect_resource(crd_name, opts) {
+                continue;
+            }
+
+            let versions = match spec.get("versions").and_then(|v| v.as_array()) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            for version in versions {
+                if version.get("served").and_then(|s| s.as_bool()) != Some(true) {
                     continue;
                 }
+                let vname = match version.get("name").and_then(|n| n.as_str()) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                let is_storage = version.get("storage").and_then(|s| s.as_bool()) == Some(true);
 
-                debug!("Collecting {} from namespace {}", ar.kind, namespace);
+                let api_version = if group.is_empty() {
+                    vname.to_string()
+                } else {
+                    format!("{}/{}", group, vname)
+                };
 
-                match self
-                    .collect_dynamic_resource(&ar, Some(namespace), opts)
-                    .await
-                {
-                    Ok(items) if !items.is_empty() => {
-                        debug!("  ✅ {} ({})", ar.kind, items.len());
-                        resources.insert(ar.kind.clone(), items);
-                    }
-                    Ok(_) => {
-                        debug!("  ⏭️  {} (0 items)", ar.kind);
+                // List across all namespaces (cluster-wide) regardless of scope;
+                // namespaced objects carry their namespace in `metadata`.
+                let api: Api<DynamicObject> =
+                    Api::all_with(self.client.clone(), &api_version, &kind);
+
+                match api.list(&ListParams::default()).await {
+                    Ok(list) => {
+                        let entry = resources.entry(kind.clone()).or_default();
+                        for item in list.items {
+                            let mut value = serde_json::to_value(&item)?;
+                            if opts.sanitize {
+                                self.sanitize_resource(&mut value);
+                            }
+                            self.redact_resource(&mut value, opts.redaction);
+                            tag_collected_version(&mut value, vname, is_storage);
+                            entry.push(value);
+                        }
+                        debug!("  ✅ {} @ {} (storage={})", kind, api_version, is_storage);
                     }
                     Err(e) => {
-                        debug!("  ⚠️  Failed to collect {}: {}", ar.kind, e);
+                        warn!("  ⚠️  Failed to list {} @ {}: {}", kind, api_version, e);
                     }
                 }
             }
@@ -198,18 +572,202 @@ impl KubeClient {
                 self.sanitize_resource(&mut value);
             }
 
+            // Redact payloads according to policy (independent of sanitize).
+            self.redact_resource(&mut value, opts.redaction);
+
             items.push(value);
         }
 
         Ok(items)
     }
 
+    /// Walk the collected objects and resolve the resources they reference,
+    /// producing a dependency closure that makes the bundle self-contained.
+    ///
+    /// Every `metadata.ownerReferences[]` entry and the well-known spec
+    /// reference locations (Pod volumes, image-pull secrets, `envFrom`/`env`)
+    /// become edges; generic `ObjectReference`-shaped fields are also followed.
+    /// Referenced objects not already present are fetched via dynamic discovery
+    /// and added as resolved nodes; anything that cannot be fetched is recorded
+    /// under `missing` so dangling references stay visible.
+    pub async fn resolve_references(
+        &self,
+        collected: &HashMap<String, Vec<Value>>,
+    ) -> Result<ReferenceGraph> {
+        let mut graph = ReferenceGraph::default();
+
+        // Index what we already have, deduping by uid where available.
+        let mut present: HashMap<ResourceKey, Option<String>> = HashMap::new();
+        let mut seen_uids: HashMap<String, ()> = HashMap::new();
+        for items in collected.values() {
+            for value in items {
+                if let Some(key) = ResourceKey::from_value(value) {
+                    let uid = value
+                        .get("metadata")
+                        .and_then(|m| m.get("uid"))
+                        .and_then(|u| u.as_str())
+                        .map(|s| s.to_string());
+                    let duplicate = match &uid {
+                        Some(uid) => seen_uids.insert(uid.clone(), ()).is_some(),
+                        None => present.contains_key(&key),
+                    };
+                    if duplicate {
+                        continue;
+                    }
+                    present.entry(key.clone()).or_insert(uid);
+                    graph.nodes.push(key);
+                }
+            }
+        }
+
+        // Gather the edges implied by every collected object.
+        let mut edges = Vec::new();
+        for items in collected.values() {
+            for value in items {
+                if let Some(from) = ResourceKey::from_value(value) {
+                    self.scan_references(&from, value, &mut edges);
+                }
+            }
+        }
+
+        // Discover the API surface once and reuse it for every reference fetch,
+        // rather than paying a full `/api`+`/apis` round-trip per dangling edge.
+        let resource_index = self.build_resource_index().await?;
+
+        // For every edge target we do not already have, try to fetch it.
+        let mut seen: HashMap<ResourceKey, ()> = HashMap::new();
+        for edge in &edges {
+            if present.contains_key(&edge.to) || seen.contains_key(&edge.to) {
+                continue;
+            }
+            seen.insert(edge.to.clone(), ());
+
+            match self.fetch_reference(&edge.to, &resource_index).await {
+                Ok(Some(value)) => {
+                    graph.nodes.push(edge.to.clone());
+                    graph.resolved.push(value);
+                }
+                Ok(None) | Err(_) => {
+                    warn!(
+                        "  ⚠️  Dangling reference: {}/{} {} in '{}'",
+                        edge.to.api_version, edge.to.kind, edge.to.name, edge.to.namespace
+                    );
+                    graph.missing.push(edge.to.clone());
+                }
+            }
+        }
+
+        graph.edges = edges;
+        Ok(graph)
+    }
+
+    /// Collect the outgoing references of a single object into `edges`.
+    fn scan_references(&self, from: &ResourceKey, value: &Value, edges: &mut Vec<ReferenceEdge>) {
+        let ns = &from.namespace;
+
+        // metadata.ownerReferences[] -> owning object in the same namespace.
+        if let Some(owners) = value
+            .get("metadata")
+            .and_then(|m| m.get("ownerReferences"))
+            .and_then(|o| o.as_array())
+        {
+            for owner in owners {
+                if let (Some(api_version), Some(kind), Some(name)) = (
+                    owner.get("apiVersion").and_then(|v| v.as_str()),
+                    owner.get("kind").and_then(|v| v.as_str()),
+                    owner.get("name").and_then(|v| v.as_str()),
+                ) {
+                    edges.push(ReferenceEdge {
+                        from: from.clone(),
+                        to: ResourceKey {
+                            api_version: api_version.to_string(),
+                            kind: kind.to_string(),
+                            namespace: ns.clone(),
+                            name: name.to_string(),
+                        },
+                        via: "ownerReference".to_string(),
+                    });
+                }
+            }
+        }
+
+        let spec = value.get("spec");
+
+        // Pod spec reference locations.
+        if let Some(spec) = spec {
+            if let Some(volumes) = spec.get("volumes").and_then(|v| v.as_array()) {
+                for vol in volumes {
+                    push_named(edges, from, ns, "ConfigMap", "v1", vol, &["configMap", "name"], "spec.volumes[].configMap");
+                    push_named(edges, from, ns, "Secret", "v1", vol, &["secret", "secretName"], "spec.volumes[].secret");
+                    push_named(edges, from, ns, "PersistentVolumeClaim", "v1", vol, &["persistentVolumeClaim", "claimName"], "spec.volumes[].persistentVolumeClaim");
+                }
+            }
+
+            if let Some(secrets) = spec.get("imagePullSecrets").and_then(|v| v.as_array()) {
+                for s in secrets {
+                    push_named(edges, from, ns, "Secret", "v1", s, &["name"], "spec.imagePullSecrets[]");
+                }
+            }
+
+            for field in ["containers", "initContainers"] {
+                if let Some(containers) = spec.get(field).and_then(|v| v.as_array()) {
+                    for c in containers {
+                        scan_container_references(edges, from, ns, c);
+                    }
+                }
+            }
+
+            // Generic ObjectReference-shaped fields directly under spec.
+            scan_object_references(edges, from, ns, spec, "spec");
+        }
+    }
+
+    /// Build a `(apiVersion, kind) -> ApiResource` index from a single
+    /// discovery run, so reference resolution can map keys to APIs without
+    /// re-discovering the cluster for every object it fetches.
+    async fn build_resource_index(
+        &self,
+    ) -> Result<HashMap<(String, String), kube::discovery::ApiResource>> {
+        let discovery = discovery::Discovery::new(self.client.clone()).run().await?;
+
+        let mut index = HashMap::new();
+        for group in discovery.groups() {
+            for (ar, _caps) in group.recommended_resources() {
+                index.insert((ar.api_version.clone(), ar.kind.clone()), ar);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Fetch a single referenced object by key, mapping it to an API via the
+    /// shared resource index.
+    async fn fetch_reference(
+        &self,
+        key: &ResourceKey,
+        index: &HashMap<(String, String), kube::discovery::ApiResource>,
+    ) -> Result<Option<Value>> {
+        let Some(ar) = index.get(&(key.api_version.clone(), key.kind.clone())) else {
+            return Ok(None);
+        };
+
+        let api: Api<DynamicObject> = if key.namespace.is_empty() {
+            Api::all_with(self.client.clone(), &ar.api_version, &ar.kind)
+        } else {
+            Api::namespaced_with(self.client.clone(), &key.namespace, &ar.api_version, &ar.kind)
+        };
+
+        match api.get_opt(&key.name).await? {
+            Some(obj) => Ok(Some(serde_json::to_value(&obj)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Determine if a resource should be collected based on options
     fn should_collect_resource(&self, kind: &str, opts: &CollectionOptions) -> bool {
+        if is_always_skipped(kind) {
+            return false;
+        }
         match kind {
-            // Always skip these
-            "ComponentStatus" | "Binding" => false,
-
             // Secrets
             "Secret" => opts.include_secrets,
 
@@ -260,4 +818,251 @@ impl KubeClient {
             obj.remove("status");
         }
     }
+
+    /// Replace Secret/ConfigMap payloads with stable placeholders that preserve
+    /// diagnostic signal (digest + length) without revealing contents, so two
+    /// bundles can be compared for "did this change" safely.
+    fn redact_resource(&self, value: &mut Value, policy: RedactionPolicy) {
+        if policy == RedactionPolicy::None {
+            return;
+        }
+
+        let kind = value
+            .get("kind")
+            .and_then(|k| k.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        match kind.as_str() {
+            "Secret" => {
+                // `data` values are base64-encoded; re-encode the placeholder so
+                // the object stays apply-friendly. `stringData` is plaintext.
+                if let Some(data) = value
+                    .get_mut("data")
+                    .and_then(|d| d.as_object_mut())
+                {
+                    for v in data.values_mut() {
+                        if let Some(encoded) = v.as_str() {
+                            let decoded = BASE64.decode(encoded).unwrap_or_default();
+                            let placeholder = redaction_placeholder(&decoded);
+                            *v = Value::String(BASE64.encode(placeholder.as_bytes()));
+                        }
+                    }
+                }
+                if let Some(string_data) = value
+                    .get_mut("stringData")
+                    .and_then(|d| d.as_object_mut())
+                {
+                    for v in string_data.values_mut() {
+                        if let Some(plain) = v.as_str() {
+                            *v = Value::String(redaction_placeholder(plain.as_bytes()));
+                        }
+                    }
+                }
+            }
+            "ConfigMap" if policy == RedactionPolicy::Aggressive => {
+                let denylist = key_denylist();
+                if let Some(data) = value
+                    .get_mut("data")
+                    .and_then(|d| d.as_object_mut())
+                {
+                    for (k, v) in data.iter_mut() {
+                        if denylist.iter().any(|re| re.is_match(k)) {
+                            if let Some(plain) = v.as_str() {
+                                *v = Value::String(redaction_placeholder(plain.as_bytes()));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Build a stable, content-free placeholder of the form
+/// `REDACTED:sha256=<first8hex>:len=<n>`.
+fn redaction_placeholder(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let first8: String = digest.iter().take(4).map(|b| format!("{:02x}", b)).collect();
+    format!("REDACTED:sha256={}:len={}", first8, bytes.len())
+}
+
+/// Default denylist of ConfigMap key names treated as sensitive.
+fn key_denylist() -> Vec<Regex> {
+    ["password", "token", ".*key.*", "secret"]
+        .iter()
+        .filter_map(|p| Regex::new(&format!("(?i)^(?:{})$", p)).ok())
+        .collect()
+}
+
+/// Kinds that are never meaningfully collectable regardless of mode.
+fn is_always_skipped(kind: &str) -> bool {
+    matches!(kind, "ComponentStatus" | "Binding")
+}
+
+/// Load and merge kubeconfig files.
+///
+/// With no explicit paths we defer to `Kubeconfig::read`, which honors the
+/// colon-separated `KUBECONFIG` env var and the default location. With explicit
+/// paths each file is read (possibly several YAML documents) and merged in
+/// order, so later files lose to earlier ones on name collisions — matching how
+/// kubectl resolves stacked configs.
+pub fn load_merged_kubeconfig(paths: &[String]) -> Result<Kubeconfig> {
+    if paths.is_empty() {
+        return Kubeconfig::read().context("Failed to read kubeconfig");
+    }
+
+    let mut merged: Option<Kubeconfig> = None;
+    for path in paths {
+        let next = Kubeconfig::read_from(path)
+            .with_context(|| format!("Failed to read kubeconfig: {}", path))?;
+        merged = Some(match merged {
+            Some(acc) => acc.merge(next).context("Failed to merge kubeconfig")?,
+            None => next,
+        });
+    }
+
+    merged.context("No kubeconfig files provided")
+}
+
+/// Resolve the active context name: an explicit `--context` if given, otherwise
+/// the merged kubeconfig's `current-context`.
+pub fn active_context(paths: &[String], explicit: Option<&str>) -> Result<Option<String>> {
+    if let Some(ctx) = explicit {
+        return Ok(Some(ctx.to_string()));
+    }
+    let kubeconfig = load_merged_kubeconfig(paths)?;
+    Ok(kubeconfig.current_context)
+}
+
+/// Return the names of all contexts defined in the merged kubeconfig.
+pub fn list_contexts(paths: &[String]) -> Result<Vec<String>> {
+    let kubeconfig = load_merged_kubeconfig(paths)?;
+    Ok(kubeconfig
+        .contexts
+        .iter()
+        .map(|c| c.name.clone())
+        .collect())
+}
+
+/// Annotate an object with the served version it was collected under and
+/// whether that version is the CRD's storage version.
+fn tag_collected_version(value: &mut Value, version: &str, is_storage: bool) {
+    if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        let annotations = metadata
+            .entry("annotations")
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if let Some(annotations) = annotations.as_object_mut() {
+            annotations.insert(
+                "ketchup.suse.com/collected-version".to_string(),
+                Value::String(version.to_string()),
+            );
+            annotations.insert(
+                "ketchup.suse.com/storage-version".to_string(),
+                Value::String(is_storage.to_string()),
+            );
+        }
+    }
+}
+
+/// Push an edge for a name found at `path` within `obj`, if present.
+fn push_named(
+    edges: &mut Vec<ReferenceEdge>,
+    from: &ResourceKey,
+    namespace: &str,
+    kind: &str,
+    api_version: &str,
+    obj: &Value,
+    path: &[&str],
+    via: &str,
+) {
+    let mut cursor = obj;
+    for segment in path {
+        match cursor.get(segment) {
+            Some(next) => cursor = next,
+            None => return,
+        }
+    }
+    if let Some(name) = cursor.as_str() {
+        edges.push(ReferenceEdge {
+            from: from.clone(),
+            to: ResourceKey {
+                api_version: api_version.to_string(),
+                kind: kind.to_string(),
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+            },
+            via: via.to_string(),
+        });
+    }
+}
+
+/// Scan a single container's `envFrom[]` and `env[].valueFrom.*Ref` fields.
+fn scan_container_references(
+    edges: &mut Vec<ReferenceEdge>,
+    from: &ResourceKey,
+    namespace: &str,
+    container: &Value,
+) {
+    if let Some(env_from) = container.get("envFrom").and_then(|v| v.as_array()) {
+        for e in env_from {
+            push_named(edges, from, namespace, "ConfigMap", "v1", e, &["configMapRef", "name"], "envFrom[].configMapRef");
+            push_named(edges, from, namespace, "Secret", "v1", e, &["secretRef", "name"], "envFrom[].secretRef");
+        }
+    }
+
+    if let Some(env) = container.get("env").and_then(|v| v.as_array()) {
+        for e in env {
+            if let Some(value_from) = e.get("valueFrom") {
+                push_named(edges, from, namespace, "ConfigMap", "v1", value_from, &["configMapKeyRef", "name"], "env[].valueFrom.configMapKeyRef");
+                push_named(edges, from, namespace, "Secret", "v1", value_from, &["secretKeyRef", "name"], "env[].valueFrom.secretKeyRef");
+            }
+        }
+    }
+}
+
+/// Follow generic `ObjectReference`-shaped fields (`kind` + `name`, optional
+/// `namespace`) found directly among the immediate members of `obj`.
+fn scan_object_references(
+    edges: &mut Vec<ReferenceEdge>,
+    from: &ResourceKey,
+    namespace: &str,
+    obj: &Value,
+    prefix: &str,
+) {
+    let Some(map) = obj.as_object() else {
+        return;
+    };
+
+    for (field, value) in map {
+        let Some(inner) = value.as_object() else {
+            continue;
+        };
+        if let (Some(kind), Some(name)) = (
+            inner.get("kind").and_then(|v| v.as_str()),
+            inner.get("name").and_then(|v| v.as_str()),
+        ) {
+            let ns = inner
+                .get("namespace")
+                .and_then(|v| v.as_str())
+                .unwrap_or(namespace)
+                .to_string();
+            let api_version = inner
+                .get("apiVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or("v1")
+                .to_string();
+            edges.push(ReferenceEdge {
+                from: from.clone(),
+                to: ResourceKey {
+                    api_version,
+                    kind: kind.to_string(),
+                    namespace: ns,
+                    name: name.to_string(),
+                },
+                via: format!("{}.{}", prefix, field),
+            });
+        }
+    }
 }
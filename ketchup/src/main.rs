@@ -1,10 +1,12 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use output::OutputManager;
 use tracing::{debug, info};
 
+mod diff;
 mod k8s;
 mod output;
+mod profiles;
 
 #[derive(Parser, Debug)]
 #[command(name = "ketchup")]
@@ -13,14 +15,46 @@ mod output;
 )]
 #[command(version)]
 struct Args {
-    /// Path to kubeconfig file (required)
+    /// Path to kubeconfig file(s), platform-separated like KUBECONFIG (when omitted, the KUBECONFIG env var is used, then in-cluster/auto-detection)
     #[arg(short, long)]
-    kubeconfig: String,
+    kubeconfig: Option<String>,
+
+    /// Use a named context instead of the kubeconfig's current-context
+    #[arg(long)]
+    context: Option<String>,
+
+    /// List the contexts available in the kubeconfig and exit
+    #[arg(long)]
+    list_contexts: bool,
+
+    /// Path to a profiles YAML file that auto-tunes collection per context
+    #[arg(long)]
+    profiles: Option<String>,
+
+    /// Diff this collection against a previous ketchup directory or archive
+    #[arg(long)]
+    diff: Option<String>,
 
     /// Namespaces to collect from (comma-separated, default: all namespaces)
     #[arg(short, long)]
     namespaces: Option<String>,
 
+    /// Namespace allow patterns (comma-separated globs like `kube-*` or `re:` regexes)
+    #[arg(long)]
+    namespace_allow: Option<String>,
+
+    /// Namespace deny patterns (comma-separated; deny wins over allow)
+    #[arg(long)]
+    namespace_deny: Option<String>,
+
+    /// Collection mode: curated (per-kind flags) or discover (full API surface)
+    #[arg(long, default_value = "curated", value_parser = ["curated", "discover"])]
+    mode: String,
+
+    /// Maximum number of concurrent list requests in flight
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
+
     /// Output directory for the archive
     #[arg(short, long, default_value = "/tmp")]
     output: String,
@@ -61,10 +95,18 @@ struct Args {
     #[arg(long)]
     crds: Option<String>,
 
+    /// Collect every served version of selected CRDs (not just the preferred one)
+    #[arg(long)]
+    all_crd_versions: bool,
+
     /// Collect raw unsanitized resources (default: sanitize for kubectl apply readiness)
     #[arg(short = 'r', long)]
     raw: bool,
 
+    /// Secret/ConfigMap redaction level: none, secrets, or aggressive
+    #[arg(long, default_value = "secrets", value_parser = ["none", "secrets", "aggressive"])]
+    redaction: String,
+
     /// Verbose logging (progress and summaries)
     #[arg(short, long)]
     verbose: bool,
@@ -76,28 +118,154 @@ struct Args {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
+    // Keep the raw matches so we can tell which flags the user actually typed,
+    // independent of their default values.
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches)?;
 
     // Initialize logging
     init_logging(args.verbose, args.debug);
 
     info!("🍅 Starting Ketchup - Kubernetes Config Collector");
-    info!("Using kubeconfig: {}", args.kubeconfig);
 
     if !args.raw {
         info!("Resources will be sanitized for kubectl apply readiness (use --raw to disable)");
     }
 
-    // Connect to Kubernetes using specified kubeconfig
-    let kube_client = k8s::KubeClient::new_client(&args.kubeconfig).await?;
+    // Resolve the kubeconfig path list from the flag or the KUBECONFIG env var.
+    let kubeconfig_paths = resolve_kubeconfig_paths(&args.kubeconfig);
+
+    // `--list-contexts` is a query: print and exit before connecting.
+    if args.list_contexts {
+        for name in k8s::list_contexts(&kubeconfig_paths)? {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    // Pick a config source: an explicit kubeconfig path, or auto-detection that
+    // prefers the in-cluster service-account token when running as a Pod.
+    let config_source = if !kubeconfig_paths.is_empty() || args.context.is_some() {
+        info!("Using kubeconfig: {:?}", kubeconfig_paths);
+        k8s::ConfigSource::Kubeconfig {
+            paths: kubeconfig_paths,
+            context: args.context.clone(),
+        }
+    } else {
+        info!("No kubeconfig specified, auto-detecting config source");
+        k8s::ConfigSource::Auto
+    };
+
+    // Resolve a matching profile (if a profiles file was supplied) so its
+    // overrides can be layered underneath the explicit CLI flags.
+    let profile = match &args.profiles {
+        Some(path) => {
+            let ctx = k8s::active_context(&kubeconfig_paths, args.context.as_deref())?
+                .unwrap_or_default();
+            profiles::select(path, &ctx)?
+        }
+        None => None,
+    };
+
+    // Effective format/compression/namespaces: an explicitly typed CLI flag
+    // always wins; otherwise the profile value (if any) is used, falling back
+    // to the built-in default.
+    let format = pick_string(
+        &args.format,
+        was_supplied(&matches, "format"),
+        profile.as_ref().and_then(|p| p.format.clone()),
+    );
+    let compression = pick_string(
+        &args.compression,
+        was_supplied(&matches, "compression"),
+        profile.as_ref().and_then(|p| p.compression.clone()),
+    );
+    let namespaces = args
+        .namespaces
+        .clone()
+        .or_else(|| profile.as_ref().and_then(|p| p.namespaces.clone()));
+
+    // Connect to Kubernetes using the resolved config source
+    let kube_client = k8s::KubeClient::new_client(&config_source).await?;
+
+    // Build collection options. An explicitly typed CLI flag wins; otherwise
+    // the profile value applies, defaulting to off.
+    let collection_opts = k8s::CollectionOptions {
+        include_secrets: pick_flag(
+            args.include_secrets,
+            was_supplied(&matches, "include_secrets"),
+            profile.as_ref().and_then(|p| p.include_secrets),
+        ),
+        include_custom_resources: pick_flag(
+            args.include_custom_resources,
+            was_supplied(&matches, "include_custom_resources"),
+            profile.as_ref().and_then(|p| p.include_custom_resources),
+        ),
+        include_events: pick_flag(
+            args.include_events,
+            was_supplied(&matches, "include_events"),
+            profile.as_ref().and_then(|p| p.include_events),
+        ),
+        include_replicasets: pick_flag(
+            args.include_replicasets,
+            was_supplied(&matches, "include_replicasets"),
+            profile.as_ref().and_then(|p| p.include_replicasets),
+        ),
+        include_endpoints: pick_flag(
+            args.include_endpoints,
+            was_supplied(&matches, "include_endpoints"),
+            profile.as_ref().and_then(|p| p.include_endpoints),
+        ),
+        include_leases: pick_flag(
+            args.include_leases,
+            was_supplied(&matches, "include_leases"),
+            profile.as_ref().and_then(|p| p.include_leases),
+        ),
+        specific_crds: args
+            .crds
+            .as_ref()
+            .map(|crds| crds.split(',').map(|s| s.trim().to_string()).collect()),
+        sanitize: if args.raw {
+            false
+        } else {
+            profile.as_ref().and_then(|p| p.sanitize).unwrap_or(true)
+        },
+        namespace_allow: parse_patterns(&args.namespace_allow)?,
+        namespace_deny: parse_patterns(&args.namespace_deny)?,
+        redaction: match args.redaction.as_str() {
+            "none" => k8s::RedactionPolicy::None,
+            "aggressive" => k8s::RedactionPolicy::Aggressive,
+            _ => k8s::RedactionPolicy::SecretsOnly,
+        },
+        max_concurrency: args.concurrency.max(1),
+        collect_all_crd_versions: args.all_crd_versions,
+        mode: match args.mode.as_str() {
+            "discover" => k8s::CollectionMode::Discover,
+            _ => k8s::CollectionMode::Curated,
+        },
+    };
 
     // Determine which namespaces to collect from
-    let namespaces = if let Some(ns_str) = &args.namespaces {
+    let namespaces = if let Some(ns_str) = &namespaces {
         let requested: Vec<String> = ns_str.split(',').map(|s| s.trim().to_string()).collect();
-        kube_client.verify_namespaces(&requested).await?
+        kube_client
+            .verify_namespaces(&requested, &collection_opts)
+            .await?
+    } else if !collection_opts.namespace_allow.is_empty() {
+        kube_client.verify_namespaces(&[], &collection_opts).await?
     } else {
         info!("No namespaces specified, collecting from all namespaces");
-        kube_client.list_namespaces().await?
+        kube_client
+            .list_namespaces()
+            .await?
+            .into_iter()
+            .filter(|ns| {
+                !collection_opts
+                    .namespace_deny
+                    .iter()
+                    .any(|p| p.matches(ns))
+            })
+            .collect()
     };
 
     info!(
@@ -107,21 +275,6 @@ async fn main() -> Result<()> {
     );
     info!("Output directory: {}", args.output);
 
-    // Build collection options
-    let collection_opts = k8s::CollectionOptions {
-        include_secrets: args.include_secrets,
-        include_custom_resources: args.include_custom_resources,
-        include_events: args.include_events,
-        include_replicasets: args.include_replicasets,
-        include_endpoints: args.include_endpoints,
-        include_leases: args.include_leases,
-        specific_crds: args
-            .crds
-            .as_ref()
-            .map(|crds| crds.split(',').map(|s| s.trim().to_string()).collect()),
-        sanitize: !args.raw,
-    };
-
     // Log what will be collected
     log_collection_plan(&collection_opts);
 
@@ -141,17 +294,29 @@ async fn main() -> Result<()> {
 
     // Save cluster resources
     let cluster_stats =
-        output_manager.save_cluster_resources(&output_dir, &cluster_resources, &args.format)?;
+        output_manager.save_cluster_resources(&output_dir, &cluster_resources, &format)?;
+
+    // Optionally capture every served version of selected CRDs
+    if collection_opts.collect_all_crd_versions {
+        info!("📦 Collecting all served CRD versions...");
+        let crd_versions = kube_client
+            .collect_crd_all_versions(&collection_opts)
+            .await?;
+        let crd_dir = format!("{}/crd-versions", output_dir);
+        output_manager.save_cluster_resources(&crd_dir, &crd_versions, &format)?;
+    }
 
     info!("📦 Collecting namespaced resources...");
-    let mut namespace_stats = Vec::new();
+    let all_ns_resources = kube_client
+        .collect_namespace_resources(&namespaces, &collection_opts)
+        .await?;
 
+    let mut namespace_stats = Vec::new();
     for namespace in &namespaces {
-        info!("📂 Collecting from namespace: {}", namespace);
-
-        let ns_resources = kube_client
-            .collect_namespace_resources(namespace, &collection_opts)
-            .await?;
+        let ns_resources = all_ns_resources
+            .get(namespace)
+            .cloned()
+            .unwrap_or_default();
 
         debug!(
             "Collected {} resource types from namespace {}",
@@ -163,12 +328,30 @@ async fn main() -> Result<()> {
             &output_dir,
             namespace,
             &ns_resources,
-            &args.format,
+            &format,
         )?;
 
         namespace_stats.push((namespace.clone(), stats));
     }
 
+    // Deterministic summary ordering regardless of collection completion order.
+    namespace_stats.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Resolve the reference closure over everything collected and emit it
+    // alongside the bundle, so users can see dangling references and
+    // reconstruct a restore order. Flatten cluster- and namespace-scoped
+    // objects into a single kind -> objects map for the resolver.
+    info!("🔗 Resolving reference graph...");
+    let mut collected: std::collections::HashMap<String, Vec<serde_json::Value>> =
+        cluster_resources.clone();
+    for ns_resources in all_ns_resources.values() {
+        for (kind, items) in ns_resources {
+            collected.entry(kind.clone()).or_default().extend(items.iter().cloned());
+        }
+    }
+    let graph = kube_client.resolve_references(&collected).await?;
+    output_manager.save_reference_graph(&output_dir, &graph)?;
+
     // Create summary
     output_manager.create_collection_summary(
         &output_dir,
@@ -177,8 +360,17 @@ async fn main() -> Result<()> {
         &collection_opts,
     )?;
 
+    // Optionally diff this collection against a previous bundle
+    if let Some(previous) = &args.diff {
+        info!("🔍 Diffing against previous bundle: {}", previous);
+        let current = diff::load_bundle(&output_dir)?;
+        let prior = diff::load_bundle(previous)?;
+        let report = diff::compute(&current, &prior);
+        diff::write_report(&output_dir, &report)?;
+    }
+
     // Handle compression
-    if let Some(archive_path) = output_manager.handle_compression(&output_dir, &args.compression)? {
+    if let Some(archive_path) = output_manager.handle_compression(&output_dir, &compression)? {
         info!("📦 Archive created: {}", archive_path);
     }
 
@@ -188,6 +380,59 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Resolve the kubeconfig path list from the `--kubeconfig` flag, falling back
+/// to the `KUBECONFIG` env var, splitting on the platform path separator.
+fn resolve_kubeconfig_paths(flag: &Option<String>) -> Vec<String> {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    let raw = flag
+        .clone()
+        .or_else(|| std::env::var("KUBECONFIG").ok())
+        .unwrap_or_default();
+    raw.split(separator)
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Whether `id` was set on the command line, as opposed to taking its default.
+fn was_supplied(matches: &clap::ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(clap::parser::ValueSource::CommandLine)
+}
+
+/// Choose a string value: an explicitly typed CLI value always wins, otherwise
+/// the profile override (if any) is used, falling back to the CLI default.
+fn pick_string(cli: &str, supplied: bool, profile: Option<String>) -> String {
+    if supplied {
+        cli.to_string()
+    } else {
+        profile.unwrap_or_else(|| cli.to_string())
+    }
+}
+
+/// Choose a boolean flag: an explicitly typed CLI flag always wins, otherwise
+/// the profile override (if any) is used, defaulting to `false`.
+fn pick_flag(cli: bool, supplied: bool, profile: Option<bool>) -> bool {
+    if supplied {
+        cli
+    } else {
+        profile.unwrap_or(false)
+    }
+}
+
+/// Parse a comma-separated list of namespace patterns into compiled matchers.
+fn parse_patterns(spec: &Option<String>) -> Result<Vec<k8s::Pattern>> {
+    match spec {
+        Some(s) => s
+            .split(',')
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(k8s::Pattern::parse)
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
 fn init_logging(verbose: bool, debug: bool) {
     let level = if debug {
         tracing::Level::DEBUG
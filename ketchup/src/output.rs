@@ -229,6 +229,61 @@ impl OutputManager {
         Ok(())
     }
 
+    /// Write the resolved reference graph to `references.yaml`.
+    ///
+    /// The file records every node in the dependency closure, the edges between
+    /// them (with the field each reference was discovered through), and any
+    /// reference that could not be resolved, so users can spot dangling
+    /// references and reconstruct a sane apply order for a restore.
+    pub fn save_reference_graph(
+        &self,
+        output_dir: &str,
+        graph: &crate::k8s::ReferenceGraph,
+    ) -> Result<()> {
+        let key_json = |k: &crate::k8s::ResourceKey| {
+            serde_json::json!({
+                "apiVersion": k.api_version,
+                "kind": k.kind,
+                "namespace": k.namespace,
+                "name": k.name,
+            })
+        };
+
+        let nodes: Vec<Value> = graph.nodes.iter().map(key_json).collect();
+        let edges: Vec<Value> = graph
+            .edges
+            .iter()
+            .map(|e| {
+                serde_json::json!({
+                    "from": key_json(&e.from),
+                    "to": key_json(&e.to),
+                    "via": e.via,
+                })
+            })
+            .collect();
+        let missing: Vec<Value> = graph.missing.iter().map(key_json).collect();
+
+        let document = serde_json::json!({
+            "node_count": graph.nodes.len(),
+            "edge_count": graph.edges.len(),
+            "resolved_count": graph.resolved.len(),
+            "missing_count": graph.missing.len(),
+            "nodes": nodes,
+            "edges": edges,
+            "missing": missing,
+            "resolved": graph.resolved,
+        });
+
+        let filename = format!("{}/references.yaml", output_dir);
+        info!("🔗 Writing reference graph: {}", filename);
+
+        let content =
+            serde_yaml::to_string(&document).context("Failed to serialize reference graph")?;
+        fs::write(&filename, content).context("Failed to write references.yaml")?;
+
+        Ok(())
+    }
+
     /// Handle compression based on user preference
     pub fn handle_compression(
         &self,
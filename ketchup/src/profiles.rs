@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use tracing::info;
+
+/// A profiles file: an ordered list of per-context override sets.
+#[derive(Debug, Deserialize)]
+struct ProfilesFile {
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+/// Collection overrides applied when the active context matches
+/// `context_pattern`. Every override is optional; unset fields fall through to
+/// the CLI defaults.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Profile {
+    /// Regex matched against the active kubeconfig context name.
+    pub context_pattern: String,
+    #[serde(default)]
+    pub include_secrets: Option<bool>,
+    #[serde(default)]
+    pub include_custom_resources: Option<bool>,
+    #[serde(default)]
+    pub include_events: Option<bool>,
+    #[serde(default)]
+    pub include_replicasets: Option<bool>,
+    #[serde(default)]
+    pub include_endpoints: Option<bool>,
+    #[serde(default)]
+    pub include_leases: Option<bool>,
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub compression: Option<String>,
+    #[serde(default)]
+    pub namespaces: Option<String>,
+    #[serde(default)]
+    pub sanitize: Option<bool>,
+}
+
+/// Load the profiles file and return the first profile whose `context_pattern`
+/// matches the active context name, if any.
+pub fn select(path: &str, context: &str) -> Result<Option<Profile>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read profiles file: {}", path))?;
+    let parsed: ProfilesFile =
+        serde_yaml::from_str(&contents).context("Failed to parse profiles file")?;
+
+    for profile in parsed.profiles {
+        let re = Regex::new(&profile.context_pattern).with_context(|| {
+            format!("Invalid context_pattern regex: {}", profile.context_pattern)
+        })?;
+        if re.is_match(context) {
+            info!(
+                "Applying profile matching context '{}' (pattern {:?})",
+                context, profile.context_pattern
+            );
+            return Ok(Some(profile));
+        }
+    }
+
+    Ok(None)
+}
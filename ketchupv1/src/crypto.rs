@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use crypto_box::{
+    aead::{Aead, AeadCore, OsRng},
+    PublicKey, SalsaBox, SecretKey,
+};
+
+/// Magic prefix identifying a ketchup sealed-box file.
+const MAGIC: &[u8; 8] = b"KETCHUP1";
+
+/// A recipient public key that payloads are sealed to. Only the holder of the
+/// matching private key (never seen by this tool) can open the result.
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    public_key: PublicKey,
+}
+
+impl Recipient {
+    /// Parse a base64-encoded 32-byte X25519 public key.
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .context("Recipient key is not valid base64")?;
+        let array: [u8; 32] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Recipient key must be 32 bytes"))?;
+        Ok(Recipient {
+            public_key: PublicKey::from(array),
+        })
+    }
+
+    /// Seal `plaintext` to this recipient using an ephemeral sender keypair.
+    ///
+    /// The output is `MAGIC || ephemeral_public_key || nonce || ciphertext`, so
+    /// a separate decrypt step can recover the ephemeral public key and nonce
+    /// it needs without any shared state.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let ephemeral_secret = SecretKey::generate(&mut OsRng);
+        let ephemeral_public = ephemeral_secret.public_key();
+
+        let salsa_box = SalsaBox::new(&self.public_key, &ephemeral_secret);
+        let nonce = SalsaBox::generate_nonce(&mut OsRng);
+        let ciphertext = salsa_box
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| anyhow::anyhow!("Failed to seal payload: {}", e))?;
+
+        let mut out = Vec::with_capacity(MAGIC.len() + 32 + nonce.len() + ciphertext.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(ephemeral_public.as_bytes());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+}
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use k8s_openapi::api::apps::v1::Deployment;
 use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Pod, Secret, Service};
+use kube::config::{Kubeconfig, KubeConfigOptions};
 use kube::{Api, Client, Config};
+use serde::Deserialize;
 use serde_json::Value;
 use tracing::{debug, info, warn};
 
@@ -10,16 +12,19 @@ pub struct KubeClient {
 }
 
 impl KubeClient {
-    /// Create a new Kubernetes client using the specified kubeconfig file
-    pub async fn new_client(kubeconfig_path: &str) -> Result<Self> {
-        info!("Loading kubeconfig from: {}", kubeconfig_path);
-
-        // Set the KUBECONFIG environment variable (safe in our single-threaded context)
-        unsafe {
-            std::env::set_var("KUBECONFIG", kubeconfig_path);
-        }
-
-        let config = Config::infer().await.context("Failed to load kubeconfig")?;
+    /// Create a new Kubernetes client from one or more kubeconfig files.
+    ///
+    /// `kubeconfig` is a platform-separated list of paths (`:` on Unix, `;` on
+    /// Windows), exactly like the `KUBECONFIG` environment variable. The files
+    /// are merged with first-definition-wins precedence (see [`load_kubeconfig`]).
+    pub async fn new_client(kubeconfig: &str) -> Result<Self> {
+        info!("Loading kubeconfig from: {}", kubeconfig);
+
+        let merged = load_kubeconfig(kubeconfig)?;
+        let config =
+            Config::from_custom_kubeconfig(merged, &KubeConfigOptions::default())
+                .await
+                .context("Failed to build client config from kubeconfig")?;
 
         let client = Client::try_from(config).context("Failed to create Kubernetes client")?;
 
@@ -223,3 +228,105 @@ impl KubeClient {
         Ok(all_secrets)
     }
 }
+
+/// Split a `KUBECONFIG`-style path list on the platform separator.
+fn split_kubeconfig_paths(raw: &str) -> Vec<String> {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    raw.split(separator)
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Load and merge a stacked kubeconfig the way `kubectl` does.
+///
+/// Each path may hold several YAML documents; the `clusters`, `contexts`, and
+/// `users` arrays are merged by `name` with first-definition-wins precedence,
+/// and `current-context` is taken from the first file that sets a non-empty
+/// value.
+pub fn load_kubeconfig(raw: &str) -> Result<Kubeconfig> {
+    let paths = split_kubeconfig_paths(raw);
+    let mut merged = Kubeconfig::default();
+    let mut have_current = false;
+
+    for path in &paths {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read kubeconfig: {}", path))?;
+
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            let config = Kubeconfig::deserialize(document)
+                .with_context(|| format!("Failed to parse kubeconfig: {}", path))?;
+
+            merge_by_name(&mut merged.clusters, config.clusters, |c| &c.name);
+            merge_by_name(&mut merged.contexts, config.contexts, |c| &c.name);
+            merge_by_name(&mut merged.auth_infos, config.auth_infos, |a| &a.name);
+
+            if !have_current {
+                if let Some(current) = config.current_context.filter(|c| !c.is_empty()) {
+                    merged.current_context = Some(current);
+                    have_current = true;
+                }
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Resolve the namespace of the kubeconfig's current context, the way
+/// `kubectl` picks a default namespace. Returns `None` when there is no current
+/// context, no matching entry, or the context sets an empty namespace.
+pub fn current_namespace(raw: &str) -> Result<Option<String>> {
+    let config = load_kubeconfig(raw)?;
+    Ok(resolve_current_namespace(&config))
+}
+
+fn resolve_current_namespace(config: &Kubeconfig) -> Option<String> {
+    let current = config.current_context.as_ref()?;
+    let context = config.contexts.iter().find(|c| &c.name == current)?;
+    context
+        .context
+        .as_ref()?
+        .namespace
+        .clone()
+        .filter(|ns| !ns.is_empty())
+}
+
+/// Resolve provenance for the kubeconfig's current context: the context name
+/// and its referenced cluster, user, and API server URL. Returns `None` when
+/// there is no usable current context, so the summary can omit the block.
+pub fn context_provenance(raw: &str) -> Result<Option<Value>> {
+    let config = load_kubeconfig(raw)?;
+    Ok(resolve_context_provenance(&config))
+}
+
+fn resolve_context_provenance(config: &Kubeconfig) -> Option<Value> {
+    let current = config.current_context.clone()?;
+    let entry = config.contexts.iter().find(|c| c.name == current)?;
+    let context = entry.context.as_ref()?;
+
+    let server = config
+        .clusters
+        .iter()
+        .find(|c| c.name == context.cluster)
+        .and_then(|c| c.cluster.as_ref())
+        .and_then(|c| c.server.clone());
+
+    Some(serde_json::json!({
+        "name": current,
+        "cluster": context.cluster,
+        "user": context.user,
+        "server": server,
+    }))
+}
+
+/// Append items from `src` to `dst` unless an entry with the same name already
+/// exists, preserving the first definition of each name.
+fn merge_by_name<T>(dst: &mut Vec<T>, src: Vec<T>, name_of: impl Fn(&T) -> &str) {
+    for item in src {
+        if !dst.iter().any(|existing| name_of(existing) == name_of(&item)) {
+            dst.push(item);
+        }
+    }
+}
@@ -1,20 +1,23 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use output::OutputManager;
 use serde_json::Value;
 use tracing::info;
 
+mod crypto;
 mod k8s;
 mod output;
+mod sink;
 
 #[derive(Parser, Debug)]
 #[command(name = "ketchup")]
 #[command(about = "Collect Kubernetes cluster configurations")]
 #[command(version)]
 struct Args {
-    /// Path to kubeconfig file (required)
+    /// Path(s) to kubeconfig file(s), platform-separated like KUBECONFIG.
+    /// Falls back to the KUBECONFIG environment variable when omitted.
     #[arg(short, long)]
-    kubeconfig: String,
+    kubeconfig: Option<String>,
 
     /// Namespaces to collect from (comma-separated)
     #[arg(short, long)]
@@ -24,18 +27,40 @@ struct Args {
     #[arg(short, long, default_value = "/tmp")]
     output: String,
 
-    /// Output format: json, yaml, or both
-    #[arg(short, long, default_value = "yaml", value_parser = ["json", "yaml", "both"])]
+    /// Output format: json, yaml, both, or multidoc (kubectl-applyable)
+    #[arg(short, long, default_value = "yaml", value_parser = ["json", "yaml", "both", "multidoc"])]
     format: String,
 
-    /// Compression: compressed, uncompressed, or both
-    #[arg(short = 'c', long, default_value = "compressed", value_parser = ["compressed", "uncompressed", "both"])]
+    /// Compression: an algorithm spec (gzip:6, zstd:19, brotli:3), or uncompressed/both.
+    /// Forced to uncompressed with --s3-bucket (streaming the archive to a bucket is not yet supported).
+    #[arg(short = 'c', long, default_value = "gzip:6")]
     compression: String,
 
     /// Collect secrets (disabled by default for security)
     #[arg(short = 's', long, default_value = "false")]
     collect_secrets: bool,
 
+    /// Recipient public key (base64 X25519) to encrypt secrets and the archive to
+    #[arg(long)]
+    recipient_key: Option<String>,
+
+    /// Verify a previously collected output directory against its manifest and exit
+    #[arg(long)]
+    verify: Option<String>,
+
+    /// Upload output to this S3 bucket instead of leaving it on local disk.
+    /// Archiving and --incremental are not yet supported for remote output.
+    #[arg(long)]
+    s3_bucket: Option<String>,
+
+    /// Key prefix to use when uploading to S3 (ignored without --s3-bucket)
+    #[arg(long, default_value = "")]
+    s3_prefix: String,
+
+    /// Only store objects that changed since the most recent prior bundle
+    #[arg(long, default_value = "false")]
+    incremental: bool,
+
     /// Verbose logging
     #[arg(short, long)]
     verbose: bool,
@@ -43,22 +68,66 @@ struct Args {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // Initialize logging
     init_logging(args.verbose);
 
+    // Verify mode: re-check a previous collection and exit.
+    if let Some(dir) = &args.verify {
+        info!("Verifying output directory: {}", dir);
+        let mismatches = output::verify_output(dir)?;
+        if mismatches.is_empty() {
+            info!("All files match the manifest");
+        } else {
+            anyhow::bail!("{} file(s) failed verification", mismatches.len());
+        }
+        return Ok(());
+    }
+
     info!("Starting Ketchup - Kubernetes Config Collector");
-    info!("Using kubeconfig: {}", args.kubeconfig);
 
-    // Connect to Kubernetes using specified kubeconfig
-    let kube_client = k8s::KubeClient::new_client(&args.kubeconfig).await?;
+    // Remote output reads the bundle back off local disk for archiving and
+    // incremental collection, which are not yet supported against a bucket.
+    // Resolve this up front so we never scrape the whole cluster only to bail.
+    if args.s3_bucket.is_some() {
+        if args.incremental {
+            anyhow::bail!("--s3-bucket does not support --incremental collection yet");
+        }
+        if args.compression != "uncompressed" {
+            info!(
+                "--s3-bucket: archive streaming to a bucket is not yet supported; \
+                 uploading objects uncompressed"
+            );
+            args.compression = "uncompressed".to_string();
+        }
+    }
+
+    // Resolve the kubeconfig from the flag, falling back to the environment.
+    let kubeconfig = match &args.kubeconfig {
+        Some(path) => path.clone(),
+        None => std::env::var("KUBECONFIG")
+            .context("--kubeconfig not provided and KUBECONFIG is not set")?,
+    };
+    info!("Using kubeconfig: {}", kubeconfig);
+
+    // Connect to Kubernetes using the resolved (possibly stacked) kubeconfig
+    let kube_client = k8s::KubeClient::new_client(&kubeconfig).await?;
 
     // Determine which namespaces to collect from
-    let requested_namespaces = if let Some(ns_str) = &args.namespaces {
+    let requested_namespaces: Vec<String> = if let Some(ns_str) = &args.namespaces {
         ns_str.split(',').map(|s| s.trim().to_string()).collect()
     } else {
-        vec!["default".to_string()]
+        match k8s::current_namespace(&kubeconfig)? {
+            Some(ns) => {
+                info!("Defaulting to current-context namespace: {}", ns);
+                vec![ns]
+            }
+            None => {
+                info!("Current context sets no namespace; defaulting to 'default'");
+                vec!["default".to_string()]
+            }
+        }
     };
 
     let verified_namespaces = kube_client.verify_namespaces(&requested_namespaces).await?;
@@ -113,7 +182,21 @@ async fn main() -> Result<()> {
         "Output format: {}, Compression: {}",
         args.format, args.compression
     );
-    let output_manager = OutputManager::new_output_manager(args.output);
+    let recipient = match &args.recipient_key {
+        Some(key) => {
+            info!("At-rest encryption enabled for secrets and archive");
+            Some(crypto::Recipient::from_base64(key)?)
+        }
+        None => None,
+    };
+    let mut output_manager = OutputManager::new_output_manager(args.output)
+        .with_recipient(recipient)
+        .with_incremental(args.incremental);
+    if let Some(bucket) = &args.s3_bucket {
+        info!("Uploading output to s3://{}/{}", bucket, args.s3_prefix);
+        let s3_sink = sink::S3Sink::new(bucket.clone(), args.s3_prefix.clone()).await?;
+        output_manager = output_manager.with_sink(Box::new(s3_sink));
+    }
     let output_dir = output_manager.create_output_directory()?;
 
     // Save resources for each namespace with new structure
@@ -228,24 +311,41 @@ async fn main() -> Result<()> {
             0
         };
 
-        namespace_stats.push((
-            namespace.clone(),
-            pods_saved,
-            services_saved,
-            deployments_saved,
-            configmaps_saved,
-            secrets_saved,
-        ));
+        let mut kind_counts = std::collections::HashMap::new();
+        kind_counts.insert("pods".to_string(), pods_saved);
+        kind_counts.insert("services".to_string(), services_saved);
+        kind_counts.insert("deployments".to_string(), deployments_saved);
+        kind_counts.insert("configmaps".to_string(), configmaps_saved);
+        if args.collect_secrets {
+            kind_counts.insert("secrets".to_string(), secrets_saved);
+        }
+
+        namespace_stats.push((namespace.clone(), kind_counts));
     }
 
-    // Create enhanced summary
-    output_manager.create_enhanced_summary(&output_dir, &namespace_stats, args.collect_secrets)?;
+    // Always record the object index so every bundle is self-describing and can
+    // serve as the reference for a later incremental collection.
+    output_manager.write_incremental_index(&output_dir)?;
+
+    // Create enhanced summary, stamped with the source cluster's provenance.
+    let context = k8s::context_provenance(&kubeconfig)?;
+    output_manager.create_enhanced_summary(
+        &output_dir,
+        &namespace_stats,
+        args.collect_secrets,
+        context.as_ref(),
+    )?;
+
+    // Write an integrity manifest so the bundle can be validated end-to-end.
+    output_manager.write_manifest(&output_dir)?;
 
     // Handle compression based on user preference
     if let Some(archive_path) = output_manager.handle_compression(&output_dir, &args.compression)? {
         info!("Archive created: {}", archive_path);
     }
 
+    output_manager.finalize()?;
+
     info!("Files saved to: {}", output_dir);
     info!("Collection completed successfully");
     Ok(())
@@ -1,20 +1,239 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
-use tracing::info;
+use tracing::{info, warn};
+
+/// A compression backend and its level, parsed from a spec like `zstd:19`,
+/// `gzip:6`, or `brotli:3`. `None` leaves the archive uncompressed.
+#[derive(Debug, Clone, Copy)]
+pub enum Compression {
+    Gzip(u32),
+    Zstd(i32),
+    Brotli(u32),
+    None,
+}
+
+impl Compression {
+    /// Parse an algorithm spec. Accepts `algo:level`, a bare `algo` (using a
+    /// sensible default level), and the legacy keyword `compressed`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (algo, level) = match spec.split_once(':') {
+            Some((a, l)) => (a, Some(l)),
+            None => (spec, None),
+        };
+
+        match algo {
+            "gzip" | "gz" | "compressed" => Ok(Compression::Gzip(parse_level(level, 6)?)),
+            "zstd" | "zst" => Ok(Compression::Zstd(parse_level(level, 19)?)),
+            "brotli" | "br" => Ok(Compression::Brotli(parse_level(level, 3)?)),
+            "none" | "uncompressed" => Ok(Compression::None),
+            other => anyhow::bail!("Unknown compression algorithm: {}", other),
+        }
+    }
+
+    /// The archive filename extension for this backend.
+    fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip(_) => "tar.gz",
+            Compression::Zstd(_) => "tar.zst",
+            Compression::Brotli(_) => "tar.br",
+            Compression::None => "tar",
+        }
+    }
+}
+
+fn parse_level<T: std::str::FromStr>(level: Option<&str>, default: T) -> Result<T> {
+    match level {
+        Some(l) => l
+            .parse::<T>()
+            .map_err(|_| anyhow::anyhow!("Invalid compression level: {}", l)),
+        None => Ok(default),
+    }
+}
+
+/// The canonical-hash index of a prior bundle, used as the baseline for an
+/// incremental collection. `objects` maps a logical object path
+/// (`<namespace>/<kind>/<name>`) to the hash recorded for it last time.
+struct Reference {
+    timestamp: String,
+    objects: HashMap<String, String>,
+}
+
+/// Per-namespace tally of how each object compared to the reference bundle.
+#[derive(Default, Clone, Copy)]
+pub struct IncrementalCounts {
+    pub new: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+}
 
 pub struct OutputManager {
     base_dir: String,
     timestamp: DateTime<Utc>,
+    recipient: Option<crate::crypto::Recipient>,
+    sink: Box<dyn crate::sink::OutputSink>,
+    /// Baseline bundle for incremental collections, if one was found.
+    reference: Option<Reference>,
+    /// Canonical hashes of every object seen this run, written out as the next
+    /// bundle's `objects.index.json`.
+    index: RefCell<HashMap<String, String>>,
+    /// Objects that matched the reference and were not re-serialized.
+    unchanged: RefCell<Vec<Value>>,
+    /// Per-namespace new/changed/unchanged tallies for the summary.
+    incremental: RefCell<HashMap<String, IncrementalCounts>>,
+    /// `(rel_path, sha256)` for every byte stream handed to the sink, so the
+    /// integrity manifest can be built from what was actually written rather
+    /// than by walking the local directory (which is empty for remote sinks).
+    emitted: RefCell<Vec<(String, String)>>,
 }
 
 impl OutputManager {
     pub fn new_output_manager(base_dir: String) -> Self {
+        let sink = Box::new(crate::sink::LocalFsSink::new(base_dir.clone()));
         Self {
             base_dir,
             timestamp: Utc::now(),
+            recipient: None,
+            sink,
+            reference: None,
+            index: RefCell::new(HashMap::new()),
+            unchanged: RefCell::new(Vec::new()),
+            incremental: RefCell::new(HashMap::new()),
+            emitted: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Enable incremental collection by locating the most recent prior
+    /// `ketchup-<timestamp>` bundle under the base directory and loading its
+    /// object index as the comparison baseline. A missing or unreadable index
+    /// simply disables incremental behaviour for this run.
+    pub fn with_incremental(mut self, enabled: bool) -> Self {
+        if !enabled {
+            return self;
         }
+        match find_reference_bundle(&self.base_dir) {
+            Some(reference) => {
+                info!(
+                    "Incremental collection against reference bundle from {}",
+                    reference.timestamp
+                );
+                self.reference = Some(reference);
+            }
+            None => info!("No prior bundle found; collecting a full baseline"),
+        }
+        self
+    }
+
+    /// Decide whether an object needs to be written. Records its canonical hash
+    /// in the run index and, when it matches the reference, appends it to the
+    /// unchanged list and reports `false` so the caller skips serialization.
+    fn should_write(&self, namespace: &str, kind: &str, name: &str, object: &Value) -> bool {
+        let logical = format!("{}/{}/{}", namespace, kind, name);
+        let hash = hex_digest(object.to_string().as_bytes());
+        self.index.borrow_mut().insert(logical.clone(), hash.clone());
+
+        let mut counts = self.incremental.borrow_mut();
+        let entry = counts.entry(namespace.to_string()).or_default();
+
+        match self.reference.as_ref().and_then(|r| r.objects.get(&logical)) {
+            Some(prev) if prev == &hash => {
+                entry.unchanged += 1;
+                let reference_timestamp = self
+                    .reference
+                    .as_ref()
+                    .map(|r| r.timestamp.clone())
+                    .unwrap_or_default();
+                self.unchanged.borrow_mut().push(serde_json::json!({
+                    "path": logical,
+                    "reference_timestamp": reference_timestamp,
+                    "hash": hash,
+                }));
+                false
+            }
+            Some(_) => {
+                entry.changed += 1;
+                true
+            }
+            None => {
+                entry.new += 1;
+                true
+            }
+        }
+    }
+
+    /// Per-namespace incremental tallies accumulated during this run.
+    pub fn incremental_counts(&self) -> HashMap<String, IncrementalCounts> {
+        self.incremental.borrow().clone()
+    }
+
+    /// Write the `unchanged.json` index and this run's `objects.index.json` so
+    /// the next incremental collection can reference this bundle.
+    pub fn write_incremental_index(&self, output_dir: &str) -> Result<()> {
+        let unchanged = self.unchanged.borrow();
+        let unchanged_doc = serde_json::json!({
+            "reference_timestamp": self.reference.as_ref().map(|r| r.timestamp.clone()),
+            "count": unchanged.len(),
+            "objects": &*unchanged,
+        });
+        let unchanged_path = format!("{}/unchanged.json", output_dir);
+        self.emit(
+            &unchanged_path,
+            serde_json::to_string_pretty(&unchanged_doc)?.as_bytes(),
+        )
+        .context("Failed to write unchanged.json")?;
+
+        let index_doc = serde_json::json!({
+            "timestamp": self.timestamp.to_rfc3339(),
+            "objects": &*self.index.borrow(),
+        });
+        let index_path = format!("{}/objects.index.json", output_dir);
+        self.emit(
+            &index_path,
+            serde_json::to_string_pretty(&index_doc)?.as_bytes(),
+        )
+        .context("Failed to write objects.index.json")?;
+
+        info!(
+            "Recorded {} unchanged object(s) against reference bundle",
+            unchanged.len()
+        );
+        Ok(())
+    }
+
+    /// Route all writes through a custom sink (e.g. an object-store backend)
+    /// instead of the default local filesystem.
+    pub fn with_sink(mut self, sink: Box<dyn crate::sink::OutputSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Write `bytes` to an absolute path by translating it into a path relative
+    /// to `base_dir` (the sink root) and handing it to the configured sink.
+    fn emit(&self, abs_path: &str, bytes: &[u8]) -> Result<()> {
+        let rel = abs_path
+            .strip_prefix(&format!("{}/", self.base_dir))
+            .unwrap_or(abs_path);
+        self.sink.write(rel, bytes)?;
+        self.emitted
+            .borrow_mut()
+            .push((rel.to_string(), hex_digest(bytes)));
+        Ok(())
+    }
+
+    /// Flush the sink once all output has been written.
+    pub fn finalize(&self) -> Result<()> {
+        self.sink.finalize()
+    }
+
+    /// Enable at-rest encryption: secret files and the final archive are sealed
+    /// to `recipient`, so only the intended support engineer can open them.
+    pub fn with_recipient(mut self, recipient: Option<crate::crypto::Recipient>) -> Self {
+        self.recipient = recipient;
+        self
     }
 
     /// Create timestamped output directory
@@ -28,109 +247,200 @@ impl OutputManager {
         Ok(output_dir)
     }
 
-    /// Save individual pods to namespace/pods/ structure
-    pub fn save_pods_individually(
+    /// Save a list of resources of a single `kind` into `namespace/<kind>/`.
+    ///
+    /// `kind` names the subdirectory (`pods`, `ingresses`, a CRD plural, ...).
+    /// When it is empty, each object's own `.kind` is used instead, so mixed
+    /// lists of core resources and CustomResources can be persisted side by
+    /// side. Secrets are sealed to the recipient when encryption is enabled.
+    pub fn save_resources_individually(
         &self,
         output_dir: &str,
         namespace: &str,
-        pods: &[Value],
+        kind: &str,
+        items: &[Value],
         format: &str,
     ) -> Result<usize> {
-        let pods_dir = format!("{}/{}/pods", output_dir, namespace);
-        fs::create_dir_all(&pods_dir).context("Failed to create namespace pods directory")?;
+        if format == "multidoc" {
+            return self.save_resources_multidoc(output_dir, namespace, kind, items);
+        }
 
         let mut saved_count = 0;
-        for pod in pods {
-            if let Some(pod_name) = pod
+        for item in items {
+            let Some(name) = item
                 .get("metadata")
                 .and_then(|m| m.get("name"))
                 .and_then(|n| n.as_str())
-            {
-                match format {
-                    "json" => {
-                        let filename = format!("{}/{}.json", pods_dir, pod_name);
-                        let content = serde_json::to_string_pretty(pod)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "yaml" => {
-                        let filename = format!("{}/{}.yaml", pods_dir, pod_name);
-                        let content = serde_yaml::to_string(pod)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "both" => {
-                        let json_file = format!("{}/{}.json", pods_dir, pod_name);
-                        let yaml_file = format!("{}/{}.yaml", pods_dir, pod_name);
+            else {
+                continue;
+            };
+
+            // Fall back to the object's own kind for mixed lists.
+            let subdir = if kind.is_empty() {
+                item.get("kind")
+                    .and_then(|k| k.as_str())
+                    .map(|k| k.to_lowercase())
+                    .unwrap_or_else(|| "unknown".to_string())
+            } else {
+                kind.to_string()
+            };
+            let dir = format!("{}/{}/{}", output_dir, namespace, subdir);
 
-                        let json_content = serde_json::to_string_pretty(pod)?;
-                        let yaml_content = serde_yaml::to_string(pod)?;
+            if !self.should_write(namespace, &subdir, name, item) {
+                continue;
+            }
 
-                        fs::write(&json_file, json_content)?;
-                        fs::write(&yaml_file, yaml_content)?;
-                        saved_count += 1;
+            // When encryption is enabled, seal secrets to the recipient and
+            // write `<name>.<fmt>.enc` instead of cleartext. `both` seals both
+            // the JSON and YAML variants so it never silently drops a copy.
+            if subdir == "secrets" || subdir == "secret" {
+                if let Some(recipient) = &self.recipient {
+                    let variants: &[(&str, Vec<u8>)] = &match format {
+                        "json" => vec![(".json.enc", serde_json::to_vec_pretty(item)?)],
+                        "both" => vec![
+                            (".json.enc", serde_json::to_vec_pretty(item)?),
+                            (".yaml.enc", serde_yaml::to_string(item)?.into_bytes()),
+                        ],
+                        _ => vec![(".yaml.enc", serde_yaml::to_string(item)?.into_bytes())],
+                    };
+                    for (ext, bytes) in variants {
+                        let filename = format!("{}/{}{}", dir, name, ext);
+                        self.emit(&filename, &recipient.seal(bytes)?)?;
                     }
-                    _ => return Err(anyhow::anyhow!("Invalid format: {}", format)),
+                    saved_count += 1;
+                    continue;
+                }
+            }
+
+            match format {
+                "json" => {
+                    let filename = format!("{}/{}.json", dir, name);
+                    self.emit(&filename, serde_json::to_string_pretty(item)?.as_bytes())?;
+                    saved_count += 1;
+                }
+                "yaml" => {
+                    let filename = format!("{}/{}.yaml", dir, name);
+                    self.emit(&filename, serde_yaml::to_string(item)?.as_bytes())?;
+                    saved_count += 1;
+                }
+                "both" => {
+                    let json_file = format!("{}/{}.json", dir, name);
+                    let yaml_file = format!("{}/{}.yaml", dir, name);
+                    self.emit(&json_file, serde_json::to_string_pretty(item)?.as_bytes())?;
+                    self.emit(&yaml_file, serde_yaml::to_string(item)?.as_bytes())?;
+                    saved_count += 1;
                 }
+                _ => return Err(anyhow::anyhow!("Invalid format: {}", format)),
             }
         }
 
-        info!("Saved {} pods to {}", saved_count, pods_dir);
+        let label = if kind.is_empty() { "mixed" } else { kind };
+        info!(
+            "Saved {} {} to {}/{}",
+            saved_count, label, namespace, label
+        );
         Ok(saved_count)
     }
 
-    /// Save individual services to namespace/services/ structure
-    pub fn save_services_individually(
+    /// Save resources as kubectl-applyable multi-document YAML: one
+    /// `<namespace>/<kind>.yaml` per kind, with every object concatenated by
+    /// `---` separators. Non-reapplyable runtime fields are stripped so the
+    /// manifests re-apply cleanly against a rebuilt cluster.
+    fn save_resources_multidoc(
         &self,
         output_dir: &str,
         namespace: &str,
-        services: &[Value],
-        format: &str,
+        kind: &str,
+        items: &[Value],
     ) -> Result<usize> {
-        let services_dir = format!("{}/{}/services", output_dir, namespace);
-        fs::create_dir_all(&services_dir)
-            .context("Failed to create namespace services directory")?;
-
+        // Group objects by kind so mixed lists still land in one file per kind.
+        let mut by_kind: std::collections::BTreeMap<String, Vec<String>> =
+            std::collections::BTreeMap::new();
         let mut saved_count = 0;
-        for service in services {
-            if let Some(service_name) = service
+
+        for item in items {
+            let Some(name) = item
                 .get("metadata")
                 .and_then(|m| m.get("name"))
                 .and_then(|n| n.as_str())
-            {
-                match format {
-                    "json" => {
-                        let filename = format!("{}/{}.json", services_dir, service_name);
-                        let content = serde_json::to_string_pretty(service)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "yaml" => {
-                        let filename = format!("{}/{}.yaml", services_dir, service_name);
-                        let content = serde_yaml::to_string(service)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "both" => {
-                        let json_file = format!("{}/{}.json", services_dir, service_name);
-                        let yaml_file = format!("{}/{}.yaml", services_dir, service_name);
+            else {
+                continue;
+            };
+
+            let subdir = if kind.is_empty() {
+                item.get("kind")
+                    .and_then(|k| k.as_str())
+                    .map(|k| k.to_lowercase())
+                    .unwrap_or_else(|| "unknown".to_string())
+            } else {
+                kind.to_string()
+            };
 
-                        let json_content = serde_json::to_string_pretty(service)?;
-                        let yaml_content = serde_yaml::to_string(service)?;
+            if !self.should_write(namespace, &subdir, name, item) {
+                continue;
+            }
 
-                        fs::write(&json_file, json_content)?;
-                        fs::write(&yaml_file, yaml_content)?;
-                        saved_count += 1;
-                    }
-                    _ => return Err(anyhow::anyhow!("Invalid format: {}", format)),
+            let cleaned = strip_runtime_fields(item);
+            by_kind
+                .entry(subdir)
+                .or_default()
+                .push(serde_yaml::to_string(&cleaned)?);
+            saved_count += 1;
+        }
+
+        for (subdir, docs) in &by_kind {
+            // Prefix every object with a `---` document marker so the file is a
+            // valid multi-document stream for `kubectl apply -f`.
+            let mut stream = String::new();
+            for doc in docs {
+                stream.push_str("---\n");
+                stream.push_str(doc);
+            }
+
+            // Honour at-rest encryption: seal the secret stream to the recipient
+            // and write `secrets.yaml.enc` rather than leaking cleartext.
+            if subdir == "secrets" || subdir == "secret" {
+                if let Some(recipient) = &self.recipient {
+                    let filename = format!("{}/{}/{}.yaml.enc", output_dir, namespace, subdir);
+                    self.emit(&filename, &recipient.seal(stream.as_bytes())?)?;
+                    continue;
                 }
             }
+
+            let filename = format!("{}/{}/{}.yaml", output_dir, namespace, subdir);
+            self.emit(&filename, stream.as_bytes())?;
         }
 
-        info!("Saved {} services to {}", saved_count, services_dir);
+        let label = if kind.is_empty() { "mixed" } else { kind };
+        info!(
+            "Saved {} {} to {}/{}.yaml (multidoc)",
+            saved_count, label, namespace, label
+        );
         Ok(saved_count)
     }
 
+    /// Save individual pods to namespace/pods/ structure
+    pub fn save_pods_individually(
+        &self,
+        output_dir: &str,
+        namespace: &str,
+        pods: &[Value],
+        format: &str,
+    ) -> Result<usize> {
+        self.save_resources_individually(output_dir, namespace, "pods", pods, format)
+    }
+
+    /// Save individual services to namespace/services/ structure
+    pub fn save_services_individually(
+        &self,
+        output_dir: &str,
+        namespace: &str,
+        services: &[Value],
+        format: &str,
+    ) -> Result<usize> {
+        self.save_resources_individually(output_dir, namespace, "services", services, format)
+    }
+
     /// Save individual deployments to namespace/deployments/ structure
     pub fn save_deployments_individually(
         &self,
@@ -139,48 +449,7 @@ impl OutputManager {
         deployments: &[Value],
         format: &str,
     ) -> Result<usize> {
-        let deployments_dir = format!("{}/{}/deployments", output_dir, namespace);
-        fs::create_dir_all(&deployments_dir)
-            .context("Failed to create namespace deployments directory")?;
-
-        let mut saved_count = 0;
-        for deployment in deployments {
-            if let Some(deployment_name) = deployment
-                .get("metadata")
-                .and_then(|m| m.get("name"))
-                .and_then(|n| n.as_str())
-            {
-                match format {
-                    "json" => {
-                        let filename = format!("{}/{}.json", deployments_dir, deployment_name);
-                        let content = serde_json::to_string_pretty(deployment)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "yaml" => {
-                        let filename = format!("{}/{}.yaml", deployments_dir, deployment_name);
-                        let content = serde_yaml::to_string(deployment)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "both" => {
-                        let json_file = format!("{}/{}.json", deployments_dir, deployment_name);
-                        let yaml_file = format!("{}/{}.yaml", deployments_dir, deployment_name);
-
-                        let json_content = serde_json::to_string_pretty(deployment)?;
-                        let yaml_content = serde_yaml::to_string(deployment)?;
-
-                        fs::write(&json_file, json_content)?;
-                        fs::write(&yaml_file, yaml_content)?;
-                        saved_count += 1;
-                    }
-                    _ => return Err(anyhow::anyhow!("Invalid format: {}", format)),
-                }
-            }
-        }
-
-        info!("Saved {} deployments to {}", saved_count, deployments_dir);
-        Ok(saved_count)
+        self.save_resources_individually(output_dir, namespace, "deployments", deployments, format)
     }
 
     /// Save individual configmaps to namespace/configmaps/ structure
@@ -191,48 +460,7 @@ impl OutputManager {
         configmaps: &[Value],
         format: &str,
     ) -> Result<usize> {
-        let configmaps_dir = format!("{}/{}/configmaps", output_dir, namespace);
-        fs::create_dir_all(&configmaps_dir)
-            .context("Failed to create namespace configmaps directory")?;
-
-        let mut saved_count = 0;
-        for configmap in configmaps {
-            if let Some(configmap_name) = configmap
-                .get("metadata")
-                .and_then(|m| m.get("name"))
-                .and_then(|n| n.as_str())
-            {
-                match format {
-                    "json" => {
-                        let filename = format!("{}/{}.json", configmaps_dir, configmap_name);
-                        let content = serde_json::to_string_pretty(configmap)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "yaml" => {
-                        let filename = format!("{}/{}.yaml", configmaps_dir, configmap_name);
-                        let content = serde_yaml::to_string(configmap)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "both" => {
-                        let json_file = format!("{}/{}.json", configmaps_dir, configmap_name);
-                        let yaml_file = format!("{}/{}.yaml", configmaps_dir, configmap_name);
-
-                        let json_content = serde_json::to_string_pretty(configmap)?;
-                        let yaml_content = serde_yaml::to_string(configmap)?;
-
-                        fs::write(&json_file, json_content)?;
-                        fs::write(&yaml_file, yaml_content)?;
-                        saved_count += 1;
-                    }
-                    _ => return Err(anyhow::anyhow!("Invalid format: {}", format)),
-                }
-            }
-        }
-
-        info!("Saved {} configmaps to {}", saved_count, configmaps_dir);
-        Ok(saved_count)
+        self.save_resources_individually(output_dir, namespace, "configmaps", configmaps, format)
     }
 
     /// Save individual secrets to namespace/secrets/ structure
@@ -243,125 +471,89 @@ impl OutputManager {
         secrets: &[Value],
         format: &str,
     ) -> Result<usize> {
-        let secrets_dir = format!("{}/{}/secrets", output_dir, namespace);
-        fs::create_dir_all(&secrets_dir).context("Failed to create namespace secrets directory")?;
-
-        let mut saved_count = 0;
-        for secret in secrets {
-            if let Some(secret_name) = secret
-                .get("metadata")
-                .and_then(|m| m.get("name"))
-                .and_then(|n| n.as_str())
-            {
-                match format {
-                    "json" => {
-                        let filename = format!("{}/{}.json", secrets_dir, secret_name);
-                        let content = serde_json::to_string_pretty(secret)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "yaml" => {
-                        let filename = format!("{}/{}.yaml", secrets_dir, secret_name);
-                        let content = serde_yaml::to_string(secret)?;
-                        fs::write(&filename, content)?;
-                        saved_count += 1;
-                    }
-                    "both" => {
-                        let json_file = format!("{}/{}.json", secrets_dir, secret_name);
-                        let yaml_file = format!("{}/{}.yaml", secrets_dir, secret_name);
-
-                        let json_content = serde_json::to_string_pretty(secret)?;
-                        let yaml_content = serde_yaml::to_string(secret)?;
-
-                        fs::write(&json_file, json_content)?;
-                        fs::write(&yaml_file, yaml_content)?;
-                        saved_count += 1;
-                    }
-                    _ => return Err(anyhow::anyhow!("Invalid format: {}", format)),
-                }
-            }
-        }
-
-        info!("Saved {} secrets to {}", saved_count, secrets_dir);
-        Ok(saved_count)
+        self.save_resources_individually(output_dir, namespace, "secrets", secrets, format)
     }
 
-    /// Create enhanced summary with per-namespace resource breakdown
+    /// Create enhanced summary with per-namespace resource breakdown.
+    ///
+    /// Each entry in `namespace_stats` carries a namespace and a map of
+    /// `kind -> count`, so the summary generalizes to arbitrary resource kinds
+    /// (CRDs, events, PVCs) rather than a fixed set of columns.
     pub fn create_enhanced_summary(
         &self,
         output_dir: &str,
-        namespace_stats: &[(String, usize, usize, usize, usize, usize)],
+        namespace_stats: &[(String, HashMap<String, usize>)],
         secrets_collected: bool,
+        context: Option<&Value>,
     ) -> Result<()> {
-        let mut total_pods = 0;
-        let mut total_services = 0;
-        let mut total_deployments = 0;
-        let mut total_configmaps = 0;
-        let mut total_secrets = 0;
+        // BTreeMap keeps the per-kind totals in a stable, sorted order.
+        let mut cluster_totals: std::collections::BTreeMap<String, usize> =
+            std::collections::BTreeMap::new();
         let mut namespace_details = serde_json::Map::new();
-
-        for (
-            namespace,
-            pod_count,
-            service_count,
-            deployment_count,
-            configmap_count,
-            secret_count,
-        ) in namespace_stats
-        {
-            total_pods += pod_count;
-            total_services += service_count;
-            total_deployments += deployment_count;
-            total_configmaps += configmap_count;
-            total_secrets += secret_count;
-
-            let mut ns_detail = serde_json::json!({
-                "pods_collected": pod_count,
-                "services_collected": service_count,
-                "deployments_collected": deployment_count,
-                "configmaps_collected": configmap_count,
-            });
-
-            if secrets_collected {
-                ns_detail["secrets_collected"] = serde_json::json!(secret_count);
-                ns_detail["total_resources"] = serde_json::json!(
-                    pod_count + service_count + deployment_count + configmap_count + secret_count
-                );
-            } else {
-                ns_detail["total_resources"] = serde_json::json!(
-                    pod_count + service_count + deployment_count + configmap_count
+        let incremental = self.incremental_counts();
+        let incremental_active = self.reference.is_some();
+
+        for (namespace, counts) in namespace_stats {
+            let mut ns_detail = serde_json::Map::new();
+            let mut ns_total = 0;
+            for (kind, count) in counts {
+                ns_detail.insert(format!("{}_collected", kind), serde_json::json!(count));
+                *cluster_totals.entry(kind.clone()).or_insert(0) += count;
+                ns_total += count;
+            }
+            ns_detail.insert("total_resources".to_string(), serde_json::json!(ns_total));
+
+            if incremental_active {
+                let c = incremental.get(namespace).copied().unwrap_or_default();
+                ns_detail.insert(
+                    "incremental".to_string(),
+                    serde_json::json!({
+                        "new": c.new,
+                        "changed": c.changed,
+                        "unchanged": c.unchanged,
+                    }),
                 );
             }
 
-            namespace_details.insert(namespace.clone(), ns_detail);
+            namespace_details.insert(namespace.clone(), Value::Object(ns_detail));
         }
 
-        let mut cluster_summary = serde_json::json!({
-            "total_namespaces": namespace_stats.len(),
-            "total_pods": total_pods,
-            "total_services": total_services,
-            "total_deployments": total_deployments,
-            "total_configmaps": total_configmaps,
-        });
-
-        if secrets_collected {
-            cluster_summary["total_secrets"] = serde_json::json!(total_secrets);
-            cluster_summary["total_resources"] = serde_json::json!(
-                total_pods + total_services + total_deployments + total_configmaps + total_secrets
-            );
-        } else {
-            cluster_summary["secrets_collected"] = serde_json::json!(false);
-            cluster_summary["total_resources"] = serde_json::json!(
-                total_pods + total_services + total_deployments + total_configmaps
-            );
+        let total_resources: usize = cluster_totals.values().sum();
+        let mut cluster_summary = serde_json::Map::new();
+        cluster_summary.insert(
+            "total_namespaces".to_string(),
+            serde_json::json!(namespace_stats.len()),
+        );
+        for (kind, count) in &cluster_totals {
+            cluster_summary.insert(format!("total_{}", kind), serde_json::json!(count));
+        }
+        cluster_summary.insert(
+            "total_resources".to_string(),
+            serde_json::json!(total_resources),
+        );
+        if !secrets_collected {
+            cluster_summary.insert("secrets_collected".to_string(), serde_json::json!(false));
+        }
+        let cluster_summary = Value::Object(cluster_summary);
+
+        let mut collection_info = serde_json::Map::new();
+        collection_info.insert(
+            "timestamp".to_string(),
+            serde_json::json!(self.timestamp.to_rfc3339()),
+        );
+        collection_info.insert("tool".to_string(), serde_json::json!("ketchup"));
+        collection_info.insert(
+            "version".to_string(),
+            serde_json::json!(env!("CARGO_PKG_VERSION")),
+        );
+        // Stamp the bundle with the cluster it came from so archives from
+        // different clusters are never mistaken for one another.
+        if let Some(context) = context {
+            collection_info.insert("context".to_string(), context.clone());
         }
 
         let summary = serde_json::json!({
-            "collection_info": {
-                "timestamp": self.timestamp.to_rfc3339(),
-                "tool": "ketchup",
-                "version": env!("CARGO_PKG_VERSION")
-            },
+            "collection_info": Value::Object(collection_info),
             "cluster_summary": cluster_summary,
             "namespace_details": namespace_details
         });
@@ -371,55 +563,292 @@ impl OutputManager {
 
         let summary_content =
             serde_yaml::to_string(&summary).context("Failed to serialize summary to YAML")?;
-        fs::write(&filename, summary_content).context("Failed to write YAML summary file")?;
+        self.emit(&filename, summary_content.as_bytes())
+            .context("Failed to write YAML summary file")?;
 
         Ok(())
     }
 
-    /// Create archive based on compression preference
+    /// Hash every file written through the sink, and write `manifest.sha256`
+    /// (sha256sum format) plus a `manifest.json` variant alongside the summary.
+    /// This guards against truncated uploads and silent corruption.
+    pub fn write_manifest(&self, output_dir: &str) -> Result<()> {
+        // Build the manifest from the bytes actually streamed to the sink. This
+        // works identically for local and remote sinks, where walking
+        // `output_dir` on disk would miss files that only exist in the bucket.
+        //
+        // `self.emitted` records paths relative to `base_dir` (the sink root),
+        // i.e. `ketchup-<ts>/ns/pods/x.yaml`. The manifest is read back by
+        // `verify_output`, which resolves entries against the bundle directory,
+        // so strip the leading `ketchup-<ts>/` segment to make the paths
+        // bundle-root-relative and keep writer and verifier in agreement.
+        let bundle_prefix = output_dir
+            .strip_prefix(&format!("{}/", self.base_dir))
+            .map(|p| format!("{}/", p));
+        let mut entries: Vec<(String, String)> = self
+            .emitted
+            .borrow()
+            .iter()
+            .map(|(rel, digest)| {
+                let rel = match &bundle_prefix {
+                    Some(prefix) => rel.strip_prefix(prefix).unwrap_or(rel),
+                    None => rel,
+                };
+                (rel.to_string(), digest.clone())
+            })
+            .collect();
+        entries.sort();
+
+        let mut sha_lines = String::new();
+        let mut json_entries = Vec::new();
+        for (rel_path, digest) in &entries {
+            sha_lines.push_str(&format!("{}  {}\n", digest, rel_path));
+            json_entries.push(serde_json::json!({ "path": rel_path, "sha256": digest }));
+        }
+
+        let sha_path = format!("{}/manifest.sha256", output_dir);
+        self.emit(&sha_path, sha_lines.as_bytes())
+            .context("Failed to write manifest.sha256")?;
+
+        let json_doc = serde_json::json!({
+            "tool": "ketchup",
+            "timestamp": self.timestamp.to_rfc3339(),
+            "files": json_entries,
+        });
+        let json_path = format!("{}/manifest.json", output_dir);
+        self.emit(
+            &json_path,
+            serde_json::to_string_pretty(&json_doc)
+                .context("Failed to serialize manifest")?
+                .as_bytes(),
+        )
+        .context("Failed to write manifest.json")?;
+
+        info!("Wrote integrity manifest for {} files", entries.len());
+        Ok(())
+    }
+
+    /// Create archive based on compression preference.
+    ///
+    /// The preference may be the legacy keyword `uncompressed`/`both` or an
+    /// algorithm spec such as `zstd:19`, `gzip:6`, or `brotli:3`.
     pub fn handle_compression(
         &self,
         output_dir: &str,
         compression: &str,
     ) -> Result<Option<String>> {
         match compression {
-            "compressed" => {
-                let archive_path = self.create_archive(output_dir)?;
-                Ok(Some(archive_path))
-            }
             "uncompressed" => {
                 info!("Skipping compression as requested");
                 Ok(None)
             }
             "both" => {
-                let archive_path = self.create_archive(output_dir)?;
+                let archive_path = self.create_archive(output_dir, Compression::Gzip(6))?;
                 info!("Files available both compressed and uncompressed");
-                Ok(Some(archive_path))
+                Ok(Some(self.maybe_encrypt_archive(archive_path)?))
             }
-            _ => {
-                anyhow::bail!(
-                    "Invalid compression: {}. Use compressed, uncompressed, or both",
-                    compression
-                );
+            spec => {
+                let algorithm = Compression::parse(spec)?;
+                let archive_path = self.create_archive(output_dir, algorithm)?;
+                Ok(Some(self.maybe_encrypt_archive(archive_path)?))
             }
         }
     }
 
-    /// Create compressed archive of the output directory
-    pub fn create_archive(&self, output_dir: &str) -> Result<String> {
-        let archive_name = format!("{}.tar.gz", output_dir);
-        info!("Creating compressed archive: {}", archive_name);
+    /// When encryption is enabled, seal the archive to the recipient, replacing
+    /// it with a `<archive>.enc` file. Otherwise the path is returned unchanged.
+    fn maybe_encrypt_archive(&self, archive_path: String) -> Result<String> {
+        let Some(recipient) = &self.recipient else {
+            return Ok(archive_path);
+        };
+
+        let bytes = fs::read(&archive_path).context("Failed to read archive for encryption")?;
+        let encrypted_path = format!("{}.enc", archive_path);
+        fs::write(&encrypted_path, recipient.seal(&bytes)?)
+            .context("Failed to write encrypted archive")?;
+        fs::remove_file(&archive_path).context("Failed to remove plaintext archive")?;
+        info!("Archive encrypted to recipient: {}", encrypted_path);
+        Ok(encrypted_path)
+    }
 
-        let tar_gz =
-            std::fs::File::create(&archive_name).context("Failed to create archive file")?;
-        let enc = flate2::write::GzEncoder::new(tar_gz, flate2::Compression::default());
-        let mut tar = tar::Builder::new(enc);
+    /// Create an archive of the output directory using the chosen backend.
+    pub fn create_archive(&self, output_dir: &str, algorithm: Compression) -> Result<String> {
+        let archive_name = format!("{}.{}", output_dir, algorithm.extension());
+        info!("Creating archive: {}", archive_name);
 
-        tar.append_dir_all(".", output_dir)
-            .context("Failed to add directory to archive")?;
-        tar.finish().context("Failed to finalize archive")?;
-        info!("Archive created successfully: {}", archive_name);
+        let file = std::fs::File::create(&archive_name).context("Failed to create archive file")?;
+
+        match algorithm {
+            Compression::Gzip(level) => {
+                let enc = flate2::write::GzEncoder::new(file, flate2::Compression::new(level));
+                append_and_finish(enc, output_dir)?;
+            }
+            Compression::Zstd(level) => {
+                let enc = zstd::stream::write::Encoder::new(file, level)
+                    .context("Failed to create zstd encoder")?
+                    .auto_finish();
+                append_and_finish(enc, output_dir)?;
+            }
+            Compression::Brotli(level) => {
+                // quality 0-11, window size 22 (4 MiB) is a good default.
+                let enc = brotli::CompressorWriter::new(file, 4096, level, 22);
+                append_and_finish(enc, output_dir)?;
+            }
+            Compression::None => {
+                append_and_finish(file, output_dir)?;
+            }
+        }
 
+        info!("Archive created successfully: {}", archive_name);
         Ok(archive_name)
     }
 }
+
+/// Find the most recent prior `ketchup-<timestamp>` bundle under `base_dir` and
+/// load its `objects.index.json`. The directory names embed a zero-padded
+/// timestamp, so the lexically greatest name is the newest bundle.
+fn find_reference_bundle(base_dir: &str) -> Option<Reference> {
+    let mut candidates: Vec<String> = fs::read_dir(base_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with("ketchup-"))
+        .collect();
+    candidates.sort();
+
+    let newest = candidates.pop()?;
+    let index_path = format!("{}/{}/objects.index.json", base_dir, newest);
+    let raw = fs::read_to_string(&index_path).ok()?;
+    let doc: Value = serde_json::from_str(&raw).ok()?;
+
+    let timestamp = doc
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .unwrap_or(&newest)
+        .to_string();
+    let objects = doc
+        .get("objects")
+        .and_then(|o| o.as_object())
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(Reference { timestamp, objects })
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Return a copy of `object` with cluster-assigned runtime fields removed so it
+/// re-applies cleanly: the server-managed `metadata` keys (`resourceVersion`,
+/// `uid`, `creationTimestamp`, `generation`, `managedFields`) and the top-level
+/// `status` block.
+fn strip_runtime_fields(object: &Value) -> Value {
+    let mut cleaned = object.clone();
+    if let Some(map) = cleaned.as_object_mut() {
+        map.remove("status");
+        if let Some(metadata) = map.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+            for field in [
+                "resourceVersion",
+                "uid",
+                "creationTimestamp",
+                "generation",
+                "managedFields",
+            ] {
+                metadata.remove(field);
+            }
+        }
+    }
+    cleaned
+}
+
+/// Re-hash every file listed in `dir`'s `manifest.sha256` and report paths
+/// whose digest no longer matches, plus any listed files that are missing.
+/// Files present in `dir` but absent from the manifest are not checked.
+pub fn verify_output(dir: &str) -> Result<Vec<String>> {
+    let manifest_path = format!("{}/manifest.sha256", dir);
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path))?;
+
+    let mut mismatches = Vec::new();
+    for line in manifest.lines() {
+        let Some((expected, rel_path)) = line.split_once("  ") else {
+            continue;
+        };
+        let full = format!("{}/{}", dir, rel_path);
+        match fs::read(&full) {
+            Ok(bytes) => {
+                if hex_digest(&bytes) != expected {
+                    warn!("Checksum mismatch: {}", rel_path);
+                    mismatches.push(rel_path.to_string());
+                }
+            }
+            Err(_) => {
+                warn!("Missing file listed in manifest: {}", rel_path);
+                mismatches.push(rel_path.to_string());
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Stream the output directory into a tar builder wrapped around `writer`.
+fn append_and_finish<W: std::io::Write>(writer: W, output_dir: &str) -> Result<()> {
+    let mut tar = tar::Builder::new(writer);
+    tar.append_dir_all(".", output_dir)
+        .context("Failed to add directory to archive")?;
+    tar.finish().context("Failed to finalize archive")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A freshly written bundle must verify clean, and corrupting a collected
+    /// file must be reported as a mismatch. This pins the manifest path
+    /// convention so the writer and `verify_output` stay in agreement.
+    #[test]
+    fn manifest_round_trips_through_verify() {
+        let base = std::env::temp_dir().join(format!("ketchup-test-{}", std::process::id()));
+        let base = base.to_string_lossy().to_string();
+        let _ = fs::remove_dir_all(&base);
+
+        let manager = OutputManager::new_output_manager(base.clone());
+        let output_dir = manager.create_output_directory().unwrap();
+
+        let pods = vec![serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": "nginx", "namespace": "default" },
+        })];
+        manager
+            .save_resources_individually(&output_dir, "default", "pods", &pods, "yaml")
+            .unwrap();
+        manager.write_manifest(&output_dir).unwrap();
+
+        assert!(
+            verify_output(&output_dir).unwrap().is_empty(),
+            "freshly written bundle should verify clean"
+        );
+
+        // Corrupt a collected file and confirm it is flagged.
+        let pod_file = format!("{}/default/pods/nginx.yaml", output_dir);
+        fs::write(&pod_file, b"tampered").unwrap();
+        assert!(
+            !verify_output(&output_dir).unwrap().is_empty(),
+            "tampered file should be reported as a mismatch"
+        );
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use tracing::info;
+
+/// A destination for collected bundle files, addressed by a path relative to
+/// the sink root (the output base directory, so paths start with the
+/// `ketchup-<timestamp>/` bundle directory). Abstracting this lets the collector target the local
+/// filesystem or a remote object store without the callers knowing which.
+pub trait OutputSink: Send + Sync {
+    /// Write `bytes` at `rel_path` (relative to the sink root).
+    fn write(&self, rel_path: &str, bytes: &[u8]) -> Result<()>;
+    /// Flush any buffered state once all writes are done.
+    fn finalize(&self) -> Result<()>;
+}
+
+/// Writes files under a base directory on the local filesystem.
+pub struct LocalFsSink {
+    root: String,
+}
+
+impl LocalFsSink {
+    pub fn new(root: String) -> Self {
+        Self { root }
+    }
+}
+
+impl OutputSink for LocalFsSink {
+    fn write(&self, rel_path: &str, bytes: &[u8]) -> Result<()> {
+        let full = format!("{}/{}", self.root, rel_path);
+        if let Some(parent) = Path::new(&full).parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        std::fs::write(&full, bytes).with_context(|| format!("Failed to write {}", full))
+    }
+
+    fn finalize(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes objects into an S3-compatible bucket under an optional key prefix.
+///
+/// Uploads are performed synchronously from within the existing Tokio runtime
+/// via `block_in_place`, so the sink presents the same blocking interface as
+/// `LocalFsSink` to its callers.
+pub struct S3Sink {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3Sink {
+    /// Build a sink from the ambient AWS environment (region, credentials).
+    pub async fn new(bucket: String, prefix: String) -> Result<Self> {
+        let config = aws_config::load_from_env().await;
+        Ok(Self {
+            client: aws_sdk_s3::Client::new(&config),
+            bucket,
+            prefix,
+        })
+    }
+
+    fn key_for(&self, rel_path: &str) -> String {
+        if self.prefix.is_empty() {
+            rel_path.to_string()
+        } else {
+            format!("{}/{}", self.prefix.trim_end_matches('/'), rel_path)
+        }
+    }
+}
+
+impl OutputSink for S3Sink {
+    fn write(&self, rel_path: &str, bytes: &[u8]) -> Result<()> {
+        let key = self.key_for(rel_path);
+        let body = bytes.to_vec();
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                self.client
+                    .put_object()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .body(body.into())
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to upload s3://{}/{}", self.bucket, key))
+            })
+        })?;
+        Ok(())
+    }
+
+    fn finalize(&self) -> Result<()> {
+        info!("All objects uploaded to s3://{}/{}", self.bucket, self.prefix);
+        Ok(())
+    }
+}